@@ -1,79 +1,68 @@
-use gatt::*;
-
-#[repr(transparent)]
-pub struct CharacteristicA(Characteristic);
-#[repr(transparent)]
-pub struct DescriptorA(Descriptor);
+#![allow(dead_code)]
 
-struct Attribute {
-    /// The type of the attribute as a UUID16, EG "Primary Service" or "Anaerobic Heart Rate Lower Limit"
-    pub att_type: usize,
-    /// Unique server-side identifer for attribute
-    pub handle: usize,
-    /// Attribute values can be any fixed length or variable length octet array, which if too large
-    /// can be sent across multiple PDUs
-    pub value: &'static mut [u8],
-}
-
-struct Descriptor {
-    attributes: &'static [Attribute],
-}
-
-struct Characteristic {
-    attributes: &'static [Attribute],
-    descriptors: &'static [Descriptor],
-}
-
-struct Service {
-    attributes: &'static [Attribute],
-    characteristics: &'static [Characteristic],
-}
+use gatt::*;
+use gatt_runtime::{
+    att::{AttServer, ConnectionId},
+    Attribute, Characteristic, Descriptor, Service,
+};
 
 trait ServiceTrait {}
 
-#[repr(transparent)]
-pub struct ServiceA(Service);
-
-#[repr(transparent)]
-pub struct ServiceB(Service);
-
-#[repr(transparent)]
-pub struct AttributeA(Attribute);
-#[repr(transparent)]
-pub struct AttributeB(Attribute);
-#[repr(transparent)]
-pub struct AttributeC(Attribute);
-#[repr(transparent)]
-pub struct AttributeD(Attribute);
+gatt_runtime::wrapper! {
+    pub struct ServiceA(Service);
+    pub struct ServiceB(Service);
+    pub struct CharacteristicA(Characteristic);
+    pub struct DescriptorA(Descriptor);
+    pub struct AttributeA(Attribute);
+    pub struct AttributeB(Attribute);
+    pub struct AttributeC(Attribute);
+    pub struct AttributeD(Attribute);
+    pub struct AttributeF(Attribute);
+}
 
 impl ServiceA {
-    fn kek(&mut self) {}
+    fn kek(&self) {}
 }
 
 gatt_server! {
-    service: ServiceA {
-        characteristic: CharacteristicA {
-            descriptor: DescriptorA {
+    service: ServiceA uuid = 0x180F {
+        characteristic: CharacteristicA uuid = "6e400001-b5a3-f393-e0a9-e50e24dcca9e"
+            properties(read, write, notify) permissions(read, write_encrypted) {
+            attribute: AttributeF<u32>,
+            descriptor: DescriptorA uuid = 0x2901 {
                 attribute: AttributeA { 3 },
                 attribute b: AttributeB { 3 },
                 attribute c: AttributeC { 3 },
-            }
+            },
         },
-        attribute: AttributeD
+        attribute: AttributeD uuid = 0x2A00 { 1 }
     },
-    service: ServiceB {
-        attribute: AttributeD { 1 }
+    service: ServiceB uuid = "6e400002-b5a3-f393-e0a9-e50e24dcca9e" {
+        attribute: AttributeD uuid = 0x2A01 { 1 }
     },
 }
 
 fn main() {
     let mut server = gatt_server::GattServer::take().unwrap();
+
+    // Discover all primary services the way a client would.
+    let mut att = AttServer::new(ConnectionId(0), 23);
+    let mut response = [0; 23];
+    let len = att
+        .process(
+            &mut server,
+            &[0x10, 0x01, 0x00, 0xFF, 0xFF, 0x00, 0x28],
+            &mut response,
+        )
+        .response
+        .unwrap();
+    println!("{:02X?}", &response[..len]);
+
     server.service_a().kek();
     let mut s = server.service_a();
     let mut c = s.characteristic_a();
     let mut d = c.descriptor_a();
-    let a = d.attribute_a();
-    let v = a.get();
+    let v = d.attribute_a().get();
 
     server
         .service_a()
@@ -81,4 +70,9 @@ fn main() {
         .descriptor_a()
         .b()
         .set(v);
+    server
+        .service_a()
+        .characteristic_a()
+        .attribute_f()
+        .set(0xDEADBEEF);
 }
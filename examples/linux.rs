@@ -0,0 +1,55 @@
+//! Serves a battery service to one client after another through BlueZ.
+//!
+//! Needs an LE adapter, `CAP_NET_BIND_SERVICE` and the GATT server of `bluetoothd` disabled.
+
+use gatt::gatt_server;
+use gatt_runtime::{Attribute, Characteristic, Service};
+
+gatt_runtime::wrapper! {
+    pub struct Battery(Service);
+    pub struct Level(Characteristic);
+    pub struct LevelValue(Attribute);
+}
+
+gatt_server! {
+    service: Battery uuid = 0x180F {
+        characteristic: Level uuid = 0x2A19 properties(read, write, notify) {
+            attribute: LevelValue<u8> = 100,
+        },
+    },
+}
+
+#[cfg(target_os = "linux")]
+fn main() -> std::io::Result<()> {
+    use gatt_runtime::{
+        att::ConnectionId,
+        linux::{serve, L2capListener, SocketTransport},
+        transport::AttTransport,
+    };
+
+    let mut server = gatt_server::GattServer::take().unwrap();
+    let listener = L2capListener::bind()?;
+    loop {
+        let socket = listener.accept()?;
+        let mut transport = SocketTransport::new(socket, ConnectionId(0), 517);
+        // Serving clears the subscriptions of the client once it disconnected.
+        serve(
+            &mut server,
+            &mut transport,
+            |server, att, transport, processed| {
+                if let Some(gatt_server::GattEvent::LevelValue { value, .. }) = processed.event {
+                    println!("The client set the level to {}", value);
+                    // Echo the level to the client if it subscribed.
+                    let mut pdu = [0; 517];
+                    if let Ok(len) = server.battery().level().notify(att, value, &mut pdu) {
+                        transport.send(&pdu[..len])?;
+                    }
+                }
+                Ok(())
+            },
+        )?;
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn main() {}
@@ -0,0 +1,3 @@
+fn main() {
+    gatt_build::export("gatt.toml").unwrap();
+}
@@ -0,0 +1,4 @@
+//! A crate whose build script exports the tree it serves, for the tests of `gatt-build`.
+
+/// The description the build script wrote.
+pub const DESCRIPTION: &str = include_str!(concat!(env!("OUT_DIR"), "/gatt.json"));
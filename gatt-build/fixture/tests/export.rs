@@ -0,0 +1,26 @@
+use gatt::gatt_server;
+use gatt_build_fixture::DESCRIPTION;
+use gatt_runtime::{Attribute, Characteristic, Descriptor, Service};
+
+gatt_runtime::wrapper! {
+    pub struct Battery(Service);
+    pub struct Level(Characteristic);
+    pub struct LevelValue(Attribute);
+    pub struct Description(Descriptor);
+    pub struct DescriptionValue(Attribute);
+    pub struct Info(Service);
+    pub struct Serial(Attribute);
+}
+
+gatt_server!(file = "gatt.toml");
+
+#[test]
+fn build_script_describes_the_served_database() {
+    let json = DESCRIPTION.split_whitespace().collect::<String>();
+    assert!(json.starts_with(r#"{"attribute_count":7,"services":[{"name":"battery","#));
+    assert!(json.contains(r#""name":"level_value","value":{"type":"u8","size":1}"#));
+
+    let mut described = String::new();
+    gatt_server::describe(&mut described).unwrap();
+    assert_eq!(DESCRIPTION, described);
+}
@@ -0,0 +1,40 @@
+//! Describes the database of a `gatt_server!(file = "...")` tree from a build script, so app code
+//! and documentation can be generated from the same file the server is.
+//!
+//! ```no_run
+//! // In the `main` of build.rs.
+//! gatt_build::export("gatt.toml").unwrap();
+//! ```
+//!
+//! The crate then finds the description at `concat!(env!("OUT_DIR"), "/gatt.json")`.
+
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Writes the JSON description of the tree in the TOML `file`, relative to the directory of the
+/// crate's `Cargo.toml`, to `$OUT_DIR/<file stem>.json` and returns its path. Cargo reruns the
+/// build script when the file changes.
+///
+/// The description is the one the generated `gatt_server::describe` writes, except that only
+/// primitive value types and arrays of them have a size, as the others are only known to the
+/// compiler. Their size is `null`.
+pub fn export(file: &str) -> io::Result<PathBuf> {
+    println!("cargo:rerun-if-changed={}", file);
+    let json = gatt_codegen::describe_file(file).map_err(|error| {
+        let messages = error.into_iter().map(|e| e.to_string()).collect::<Vec<_>>();
+        io::Error::new(io::ErrorKind::InvalidData, messages.join("\n"))
+    })?;
+    // The tree loaded, so the file has a `.toml` extension and a stem.
+    let stem = Path::new(file).file_stem().unwrap_or_default();
+    let out_dir = env::var_os("OUT_DIR").ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "`OUT_DIR` is not set, `export` is meant to be called from a build script",
+        )
+    })?;
+    let path = Path::new(&out_dir).join(stem).with_extension("json");
+    fs::write(&path, json)?;
+    Ok(path)
+}
@@ -0,0 +1,237 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+use crate::{accessor_name, flags, AttributeKind, GattServer};
+
+/// Generates a `gatt_client` module with a `GattClient` which discovers the services and
+/// characteristics of the tree on a server and reads, writes and subscribes to their values.
+pub fn gatt_client(server: &GattServer) -> TokenStream {
+    let mut fields = vec![];
+    let mut discovery = vec![];
+    let mut methods = vec![];
+    let mut notification_variants = vec![];
+    let mut notification_decoders = vec![];
+    // Variable length values are handed out as a slice of the PDU they arrived in.
+    let mut borrows_pdu = false;
+
+    for service in &server.services {
+        let service_uuid = service.uuid;
+        let characteristics = &server.characteristics[service.characteristics.clone()];
+        let names = characteristics
+            .iter()
+            .map(|c| accessor_name(&c.name, &c.type_name))
+            .collect::<Vec<_>>();
+        let uuids = characteristics.iter().map(|c| c.uuid);
+        discovery.push(if names.is_empty() {
+            quote! { att.find_service(x, #service_uuid)?; }
+        } else {
+            quote! {
+                let service = att.find_service(x, #service_uuid)?;
+                #(let #names = att.find_characteristic(x, service, #uuids)?;)*
+            }
+        });
+
+        for (characteristic, name) in characteristics.iter().zip(&names) {
+            fields.push(name.clone());
+            let (value_type, variable) =
+                match &server.attributes[characteristic.attributes.start + 1].kind {
+                    AttributeKind::Value {
+                        value_type,
+                        variable,
+                        ..
+                    } => (value_type, *variable),
+                    _ => unreachable!("Characteristic declarations are followed by their value"),
+                };
+            let properties = characteristic.properties;
+
+            if properties & flags::PROPERTY_READ != 0 {
+                let fn_name = format_ident!("read_{}", name);
+                methods.push(if variable {
+                    quote! {
+                        /// Reads the value into `buffer` and returns its length.
+                        pub fn #fn_name<X: ::gatt_runtime::client::Exchange>(
+                            &self,
+                            x: &mut X,
+                            buffer: &mut [u8],
+                        ) -> Result<usize, ::gatt_runtime::client::ClientError<X::Error>> {
+                            self.att.read(x, self.#name.value, buffer)
+                        }
+                    }
+                } else {
+                    quote! {
+                        pub fn #fn_name<X: ::gatt_runtime::client::Exchange>(
+                            &self,
+                            x: &mut X,
+                        ) -> Result<#value_type, ::gatt_runtime::client::ClientError<X::Error>> {
+                            let mut buffer = [0; <#value_type as ::gatt_runtime::GattValue>::SIZE];
+                            let len = self.att.read(x, self.#name.value, &mut buffer)?;
+                            if len != buffer.len()
+                                || !<#value_type as ::gatt_runtime::GattValue>::is_valid(&buffer)
+                            {
+                                return Err(::gatt_runtime::client::ClientError::InvalidValue);
+                            }
+                            Ok(::gatt_runtime::GattValue::from_bytes(&buffer))
+                        }
+                    }
+                });
+            }
+
+            // Each write property has its own method, as the server only takes the kind of write
+            // the characteristic offers.
+            let writes = [
+                (
+                    flags::PROPERTY_WRITE,
+                    format_ident!("write_{}", name),
+                    quote! { write },
+                ),
+                (
+                    flags::PROPERTY_WRITE_WITHOUT_RESPONSE,
+                    format_ident!("write_{}_without_response", name),
+                    quote! { write_command },
+                ),
+            ];
+            for (property, fn_name, write) in writes.iter() {
+                if properties & property == 0 {
+                    continue;
+                }
+                methods.push(if variable {
+                    quote! {
+                        pub fn #fn_name<X: ::gatt_runtime::client::Exchange>(
+                            &self,
+                            x: &mut X,
+                            value: &[u8],
+                        ) -> Result<(), ::gatt_runtime::client::ClientError<X::Error>> {
+                            self.att.#write(x, self.#name.value, value)
+                        }
+                    }
+                } else {
+                    quote! {
+                        pub fn #fn_name<X: ::gatt_runtime::client::Exchange>(
+                            &self,
+                            x: &mut X,
+                            value: #value_type,
+                        ) -> Result<(), ::gatt_runtime::client::ClientError<X::Error>> {
+                            let mut buffer = [0; <#value_type as ::gatt_runtime::GattValue>::SIZE];
+                            ::gatt_runtime::GattValue::to_bytes(&value, &mut buffer);
+                            self.att.#write(x, self.#name.value, &buffer)
+                        }
+                    }
+                });
+            }
+
+            if properties & (flags::PROPERTY_NOTIFY | flags::PROPERTY_INDICATE) != 0 {
+                let fn_name = format_ident!("subscribe_{}", name);
+                // Notifications need no confirmation, so they are preferred when both are offered.
+                let bits = if properties & flags::PROPERTY_NOTIFY != 0 {
+                    quote! { ::gatt_runtime::att::NOTIFICATIONS_ENABLED }
+                } else {
+                    quote! { ::gatt_runtime::att::INDICATIONS_ENABLED }
+                };
+                methods.push(quote! {
+                    /// Enables notifications of the value, or indications if it only indicates.
+                    pub fn #fn_name<X: ::gatt_runtime::client::Exchange>(
+                        &self,
+                        x: &mut X,
+                    ) -> Result<(), ::gatt_runtime::client::ClientError<X::Error>> {
+                        let configuration = self.#name.configuration.ok_or(
+                            ::gatt_runtime::client::ClientError::NotFound(::gatt_runtime::Uuid::Uuid16(0x2902)),
+                        )?;
+                        self.att.write(x, configuration, &#bits.to_le_bytes())
+                    }
+                });
+
+                let variant = &characteristic.type_name.segments.last().unwrap().ident;
+                if variable {
+                    borrows_pdu = true;
+                    notification_variants.push(quote! { #variant(&'a [u8]) });
+                    notification_decoders.push(quote! {
+                        if handle == self.#name.value {
+                            return Some(Notification::#variant(value));
+                        }
+                    });
+                } else {
+                    notification_variants.push(quote! { #variant(#value_type) });
+                    notification_decoders.push(quote! {
+                        if handle == self.#name.value {
+                            if value.len() != <#value_type as ::gatt_runtime::GattValue>::SIZE
+                                || !<#value_type as ::gatt_runtime::GattValue>::is_valid(value)
+                            {
+                                return None;
+                            }
+                            return Some(Notification::#variant(::gatt_runtime::GattValue::from_bytes(value)));
+                        }
+                    });
+                }
+            }
+        }
+    }
+
+    let lifetime = if borrows_pdu {
+        quote! { <'a> }
+    } else {
+        quote! {}
+    };
+
+    quote! {
+        mod gatt_client {
+            use super::*;
+
+            /// The handles of the services and characteristics a server offers, found by their
+            /// UUIDs.
+            ///
+            /// Every request takes the `Exchange` carrying it to the server.
+            pub struct GattClient {
+                att: ::gatt_runtime::client::AttClient,
+                #(pub #fields: ::gatt_runtime::client::Discovered,)*
+            }
+
+            /// A value a server notified or indicated.
+            pub enum Notification #lifetime {
+                #(#notification_variants,)*
+            }
+
+            impl GattClient {
+                /// Negotiates an MTU of up to `rx_mtu` and discovers every service and
+                /// characteristic, which fails if the server lacks one of them.
+                pub fn discover<X: ::gatt_runtime::client::Exchange>(
+                    x: &mut X,
+                    rx_mtu: u16,
+                ) -> Result<Self, ::gatt_runtime::client::ClientError<X::Error>> {
+                    let mut att = ::gatt_runtime::client::AttClient::new();
+                    att.exchange_mtu(x, rx_mtu)?;
+                    #(#discovery)*
+                    Ok(GattClient {
+                        att,
+                        #(#fields,)*
+                    })
+                }
+
+                /// The MTU negotiated with the server.
+                pub fn mtu(&self) -> u16 {
+                    self.att.mtu()
+                }
+
+                #(#methods)*
+
+                /// Decodes a Handle Value Notification or Indication of a subscribed value,
+                /// `None` for other PDUs and values which do not decode.
+                ///
+                /// Indications have to be confirmed with [`GattClient::confirm`].
+                #[allow(unused_variables)]
+                pub fn notification<'a>(&self, pdu: &'a [u8]) -> Option<Notification #lifetime> {
+                    let (handle, value, _) = ::gatt_runtime::client::handle_value(pdu)?;
+                    #(#notification_decoders)*
+                    None
+                }
+
+                /// Confirms an indication, so the server can send the next one.
+                pub fn confirm<X: ::gatt_runtime::client::Exchange>(
+                    &self,
+                    x: &mut X,
+                ) -> Result<(), ::gatt_runtime::client::ClientError<X::Error>> {
+                    self.att.confirm(x)
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    parse::{ParseStream, Result},
+    parse_quote, Attribute, Data, DataEnum, DeriveInput, Error, Expr, Fields, Ident, Index, Meta,
+    NestedMeta, Token, Type,
+};
+
+/// The integer type of a `#[repr(u8)]` style attribute, which may be combined with others as in
+/// `#[repr(C, u8)]` or `#[repr(u8, align(2))]`.
+fn repr_type(attrs: &[Attribute]) -> Option<Type> {
+    attrs
+        .iter()
+        .filter(|a| a.path.is_ident("repr"))
+        .filter_map(|a| match a.parse_meta() {
+            Ok(Meta::List(list)) => Some(list.nested),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|nested| match nested {
+            NestedMeta::Meta(Meta::Path(path)) => path.get_ident().cloned(),
+            _ => None,
+        })
+        .find(|ident| {
+            ["u8", "u16", "u32", "u64", "i8", "i16", "i32", "i64"].contains(&&*ident.to_string())
+        })
+        .map(|ident| parse_quote!(#ident))
+}
+
+/// The bits type of a `#[gatt(bits = u8)]` attribute, which marks bitflags types.
+fn bits_type(attrs: &[Attribute]) -> Result<Option<Type>> {
+    attrs
+        .iter()
+        .find(|a| a.path.is_ident("gatt"))
+        .map(|a| {
+            a.parse_args_with(|input: ParseStream| {
+                let key = input.parse::<Ident>()?;
+                if key != "bits" {
+                    return Err(Error::new(key.span(), "Expected `bits = <integer type>`"));
+                }
+                input.parse::<Token![=]>()?;
+                input.parse::<Type>()
+            })
+        })
+        .transpose()
+}
+
+/// Implements `GattValue` for structs field by field, for fieldless enums through their integer
+/// representation and for bitflags types marked `#[gatt(bits = u8)]` through their bits.
+pub fn gatt_value(input: DeriveInput) -> Result<TokenStream> {
+    if let Some(bits) = bits_type(&input.attrs)? {
+        return Ok(bitflags(&input, &bits));
+    }
+    match &input.data {
+        Data::Struct(data) => Ok(structure(&input, &data.fields)),
+        Data::Enum(data) => enumeration(&input, data),
+        Data::Union(_) => Err(Error::new_spanned(
+            &input.ident,
+            "GattValue cannot be derived for unions",
+        )),
+    }
+}
+
+/// The impl header with a `GattValue` bound on every type parameter.
+fn header(input: &DeriveInput) -> TokenStream {
+    let name = &input.ident;
+    let mut generics = input.generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(parse_quote!(::gatt_runtime::GattValue));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    quote! { impl #impl_generics ::gatt_runtime::GattValue for #name #ty_generics #where_clause }
+}
+
+fn structure(input: &DeriveInput, fields: &Fields) -> TokenStream {
+    let header = header(input);
+    let types = fields.iter().map(|f| &f.ty).collect::<Vec<_>>();
+    let members = fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| match &f.ident {
+            Some(ident) => quote!(#ident),
+            None => {
+                let index = Index::from(i);
+                quote!(#index)
+            }
+        })
+        .collect::<Vec<_>>();
+    let vars = (0..types.len())
+        .map(|i| format_ident!("field_{}", i))
+        .collect::<Vec<_>>();
+    let construct = match fields {
+        Fields::Named(_) => quote! { Self { #(#members: #vars),* } },
+        Fields::Unnamed(_) => quote! { Self(#(#vars),*) },
+        Fields::Unit => quote! { Self },
+    };
+
+    quote! {
+        #header {
+            const SIZE: usize = 0 #(+ <#types as ::gatt_runtime::GattValue>::SIZE)*;
+
+            fn to_bytes(&self, buffer: &mut [u8]) {
+                let mut offset = 0;
+                #(
+                    let size = <#types as ::gatt_runtime::GattValue>::SIZE;
+                    ::gatt_runtime::GattValue::to_bytes(&self.#members, &mut buffer[offset..offset + size]);
+                    offset += size;
+                )*
+                let _ = offset;
+            }
+
+            fn from_bytes(buffer: &[u8]) -> Self {
+                let mut offset = 0;
+                #(
+                    let size = <#types as ::gatt_runtime::GattValue>::SIZE;
+                    let #vars = <#types as ::gatt_runtime::GattValue>::from_bytes(&buffer[offset..offset + size]);
+                    offset += size;
+                )*
+                let _ = offset;
+                #construct
+            }
+
+            fn is_valid(buffer: &[u8]) -> bool {
+                let mut offset = 0;
+                #(
+                    let size = <#types as ::gatt_runtime::GattValue>::SIZE;
+                    if !<#types as ::gatt_runtime::GattValue>::is_valid(&buffer[offset..offset + size]) {
+                        return false;
+                    }
+                    offset += size;
+                )*
+                let _ = offset;
+                true
+            }
+
+            const ZEROED_IS_VALID: bool = true #(&& <#types as ::gatt_runtime::GattValue>::ZEROED_IS_VALID)*;
+        }
+    }
+}
+
+fn enumeration(input: &DeriveInput, data: &DataEnum) -> Result<TokenStream> {
+    let repr = repr_type(&input.attrs).ok_or_else(|| {
+        Error::new_spanned(
+            &input.ident,
+            "Enums need an integer representation such as `#[repr(u8)]` to derive GattValue",
+        )
+    })?;
+    if let Some(variant) = data.variants.iter().find(|v| !v.fields.is_empty()) {
+        return Err(Error::new_spanned(
+            &variant.fields,
+            "Only enums without fields can derive GattValue",
+        ));
+    }
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "Enums deriving GattValue cannot be generic",
+        ));
+    }
+    let name = &input.ident;
+    let variants = data.variants.iter().map(|v| &v.ident).collect::<Vec<_>>();
+    // Casting a unit variant yields its discriminant, whether explicit or implicit.
+    let discriminants = variants
+        .iter()
+        .map(|v| -> Expr { parse_quote!(#name::#v as #repr) })
+        .collect::<Vec<_>>();
+
+    Ok(quote! {
+        impl ::gatt_runtime::GattValue for #name {
+            const SIZE: usize = <#repr as ::gatt_runtime::GattValue>::SIZE;
+
+            fn to_bytes(&self, buffer: &mut [u8]) {
+                let discriminant = match self {
+                    #(#name::#variants => #discriminants,)*
+                };
+                ::gatt_runtime::GattValue::to_bytes(&discriminant, buffer);
+            }
+
+            fn from_bytes(buffer: &[u8]) -> Self {
+                let discriminant = <#repr as ::gatt_runtime::GattValue>::from_bytes(buffer);
+                #(
+                    if discriminant == #discriminants {
+                        return #name::#variants;
+                    }
+                )*
+                panic!("Invalid discriminant for {}", stringify!(#name))
+            }
+
+            fn is_valid(buffer: &[u8]) -> bool {
+                let discriminant = <#repr as ::gatt_runtime::GattValue>::from_bytes(buffer);
+                false #(|| discriminant == #discriminants)*
+            }
+
+            const ZEROED_IS_VALID: bool = false #(|| #discriminants == 0)*;
+        }
+    })
+}
+
+fn bitflags(input: &DeriveInput, bits: &Type) -> TokenStream {
+    let name = &input.ident;
+    quote! {
+        impl ::gatt_runtime::GattValue for #name {
+            const SIZE: usize = <#bits as ::gatt_runtime::GattValue>::SIZE;
+
+            fn to_bytes(&self, buffer: &mut [u8]) {
+                ::gatt_runtime::GattValue::to_bytes(&self.bits(), buffer);
+            }
+
+            fn from_bytes(buffer: &[u8]) -> Self {
+                Self::from_bits_retain(<#bits as ::gatt_runtime::GattValue>::from_bytes(buffer))
+            }
+        }
+    }
+}
@@ -0,0 +1,301 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, ToTokens};
+use syn::{Expr, ExprLit, Lit, LitStr, Result, Type};
+
+use crate::{accessor_name, flags, Attribute, AttributeKind, GattServer};
+
+/// A JSON value, just enough to describe a database.
+enum Json {
+    Null,
+    Number(usize),
+    /// The number of bytes of a value type, which only the compiler knows.
+    Size(Box<Type>),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(&'static str, Json)>),
+}
+
+impl Json {
+    fn string(value: impl ToString) -> Json {
+        Json::String(value.to_string())
+    }
+
+    fn strings(values: Vec<&str>) -> Json {
+        Json::Array(values.into_iter().map(Json::string).collect())
+    }
+
+    /// Writes the value pretty printed with two spaces of indentation per level.
+    fn write(&self, out: &mut impl Output, indent: usize) {
+        let (open, close, items) = match self {
+            Json::Null => return out.push_str("null"),
+            Json::Number(value) => return out.push_str(&value.to_string()),
+            Json::Size(value_type) => return out.size(value_type),
+            Json::String(value) => return escape(out, value),
+            Json::Array(items) => (
+                "[",
+                "]",
+                items.iter().map(|i| (None, i)).collect::<Vec<_>>(),
+            ),
+            Json::Object(items) => ("{", "}", items.iter().map(|(k, i)| (Some(*k), i)).collect()),
+        };
+        out.push_str(open);
+        for (i, (key, item)) in items.iter().enumerate() {
+            out.push_str(if i == 0 { "\n" } else { ",\n" });
+            out.push_str(&"  ".repeat(indent + 1));
+            if let Some(key) = key {
+                escape(out, key);
+                out.push_str(": ");
+            }
+            item.write(out, indent + 1);
+        }
+        if !items.is_empty() {
+            out.push_str("\n");
+            out.push_str(&"  ".repeat(indent));
+        }
+        out.push_str(close);
+    }
+}
+
+/// Where a description is written to.
+trait Output {
+    fn push_str(&mut self, text: &str);
+
+    /// Writes the number of bytes of a value type.
+    fn size(&mut self, value_type: &Type);
+}
+
+/// Collects the statements of the generated `describe` function, which writes the text known
+/// to the macro as it is and formats the sizes of value types in between.
+#[derive(Default)]
+struct Writer {
+    text: String,
+    statements: Vec<TokenStream>,
+}
+
+impl Writer {
+    fn flush(&mut self) {
+        if !self.text.is_empty() {
+            let text = std::mem::take(&mut self.text);
+            self.statements.push(quote! { out.write_str(#text)?; });
+        }
+    }
+}
+
+impl Output for Writer {
+    fn push_str(&mut self, text: &str) {
+        self.text.push_str(text);
+    }
+
+    fn size(&mut self, value_type: &Type) {
+        self.flush();
+        self.statements.push(quote! {
+            ::core::write!(out, "{}", <#value_type as ::gatt_runtime::GattValue>::SIZE)?;
+        });
+    }
+}
+
+/// A description written without the compiler, as by a build script, which only knows the sizes
+/// of primitive value types and arrays of them. Other sizes are `null`.
+impl Output for String {
+    fn push_str(&mut self, text: &str) {
+        String::push_str(self, text);
+    }
+
+    fn size(&mut self, value_type: &Type) {
+        match primitive_size(value_type) {
+            Some(size) => self.push_str(&size.to_string()),
+            None => self.push_str("null"),
+        }
+    }
+}
+
+fn escape(out: &mut impl Output, value: &str) {
+    out.push_str("\"");
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push_str(c.encode_utf8(&mut [0; 4])),
+        }
+    }
+    out.push_str("\"");
+}
+
+/// The number of bytes of a primitive value type or an array of them.
+fn primitive_size(value_type: &Type) -> Option<usize> {
+    match value_type {
+        Type::Path(path) => match path.path.get_ident()?.to_string().as_str() {
+            "u8" | "i8" | "bool" => Some(1),
+            "u16" | "i16" => Some(2),
+            "u32" | "i32" | "f32" => Some(4),
+            "u64" | "i64" | "f64" => Some(8),
+            "u128" | "i128" => Some(16),
+            _ => None,
+        },
+        Type::Array(array) => match &array.len {
+            Expr::Lit(ExprLit {
+                lit: Lit::Int(len), ..
+            }) => Some(primitive_size(&array.elem)? * len.base10_parse::<usize>().ok()?),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The number of bytes of a `[u8; N]` value type, which attributes with a plain size have.
+fn array_len(value_type: &Type) -> Option<usize> {
+    match value_type {
+        Type::Array(array) => match &array.len {
+            Expr::Lit(expr) => match &expr.lit {
+                Lit::Int(len) => len.base10_parse().ok(),
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The handle, type and permissions of an attribute, and how its value is encoded.
+fn attribute(attribute: &Attribute) -> Json {
+    let mut object = vec![
+        ("handle", Json::Number(attribute.handle as usize)),
+        ("uuid", Json::string(attribute.att_type)),
+        (
+            "permissions",
+            Json::strings(flags::permission_names(attribute.permissions)),
+        ),
+    ];
+    if let AttributeKind::Value {
+        name,
+        type_name,
+        value_type,
+        variable,
+        ..
+    } = &attribute.kind
+    {
+        object.push(("name", Json::string(accessor_name(name, type_name))));
+        let value = match (array_len(value_type), variable) {
+            (Some(len), true) => vec![("max_size", Json::Number(len))],
+            (Some(len), false) => vec![("size", Json::Number(len))],
+            (None, _) => vec![
+                (
+                    "type",
+                    Json::string(value_type.to_token_stream().to_string().replace(' ', "")),
+                ),
+                ("size", Json::Size(value_type.clone())),
+            ],
+        };
+        object.push(("value", Json::Object(value)));
+    }
+    Json::Object(object)
+}
+
+/// Describes the services, characteristics and descriptors of the server with their handles.
+fn database(server: &GattServer) -> Json {
+    let attributes = |range: &std::ops::Range<usize>| {
+        Json::Array(
+            server.attributes[range.clone()]
+                .iter()
+                .map(attribute)
+                .collect(),
+        )
+    };
+    let services = server
+        .services
+        .iter()
+        .enumerate()
+        .map(|(i, s)| {
+            let characteristics = server.characteristics[s.characteristics.clone()]
+                .iter()
+                .map(|c| {
+                    let descriptors = server.descriptors[c.descriptors.clone()]
+                        .iter()
+                        .map(|d| {
+                            let name = match &d.type_name {
+                                Some(type_name) => Json::string(accessor_name(&d.name, type_name)),
+                                // Synthesized descriptors have no name.
+                                None => Json::Null,
+                            };
+                            Json::Object(vec![
+                                ("name", name),
+                                ("uuid", Json::string(d.uuid)),
+                                ("attributes", attributes(&d.attributes)),
+                            ])
+                        })
+                        .collect();
+                    let declaration = &server.attributes[c.attributes.start];
+                    Json::Object(vec![
+                        ("name", Json::string(accessor_name(&c.name, &c.type_name))),
+                        ("uuid", Json::string(c.uuid)),
+                        ("handle", Json::Number(declaration.handle as usize)),
+                        (
+                            "value_handle",
+                            Json::Number(declaration.handle as usize + 1),
+                        ),
+                        (
+                            "properties",
+                            Json::strings(flags::property_names(c.properties)),
+                        ),
+                        (
+                            "attributes",
+                            attributes(&(c.attributes.start + 1..c.attributes.end)),
+                        ),
+                        ("descriptors", Json::Array(descriptors)),
+                    ])
+                })
+                .collect();
+            let declaration = &server.attributes[s.attributes.start];
+            // Services are laid out back to back, so a group ends where the next one starts.
+            let end = server
+                .services
+                .get(i + 1)
+                .map_or(server.attributes.len(), |next| next.attributes.start);
+            Json::Object(vec![
+                ("name", Json::string(accessor_name(&s.name, &s.type_name))),
+                ("uuid", Json::string(s.uuid)),
+                ("handle", Json::Number(declaration.handle as usize)),
+                ("end_handle", Json::Number(end)),
+                (
+                    "attributes",
+                    attributes(&(s.attributes.start + 1..s.attributes.end)),
+                ),
+                ("characteristics", Json::Array(characteristics)),
+            ])
+        })
+        .collect();
+    Json::Object(vec![
+        ("attribute_count", Json::Number(server.attributes.len())),
+        ("services", Json::Array(services)),
+    ])
+}
+
+/// The `describe` function of the generated module, which writes the description of the
+/// database as JSON.
+pub fn describe(server: &GattServer) -> TokenStream {
+    let mut out = Writer::default();
+    database(server).write(&mut out, 0);
+    out.push_str("\n");
+    out.flush();
+    let statements = out.statements;
+    quote! {
+        /// Writes a JSON description of the services, characteristics and descriptors with their
+        /// UUIDs, handles, properties, permissions and value sizes to `out`.
+        pub fn describe<W: ::core::fmt::Write>(out: &mut W) -> ::core::fmt::Result {
+            #(#statements)*
+            Ok(())
+        }
+    }
+}
+
+/// Describes the database of the tree in the TOML `file`, relative to the directory of the
+/// crate's `Cargo.toml`, as JSON.
+pub fn describe_file(file: &str) -> Result<String> {
+    let server_parsed = crate::file::load(&LitStr::new(file, Span::call_site()))?;
+    let server = crate::lay_out(&server_parsed)?;
+    let mut out = String::new();
+    database(&server).write(&mut out, 0);
+    out.push('\n');
+    Ok(out)
+}
@@ -0,0 +1,240 @@
+use std::{env, fmt, fs, path::Path};
+
+use proc_macro2::{Group, Span, TokenStream, TokenTree};
+use serde::Deserialize;
+use syn::{
+    parse::{Parse, Result},
+    Error, LitStr,
+};
+use toml::Spanned;
+
+use crate::{sig::AssignedNumbers, GattServerParsed, StructLike};
+
+/// Where a struct loaded from a file was declared.
+///
+/// Spans cannot point into other files, so errors about the struct name the line instead.
+#[derive(Debug, Clone)]
+pub struct Origin {
+    file: String,
+    line: usize,
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.file, self.line)
+    }
+}
+
+impl Origin {
+    /// Prefixes `error` with the file and line, unless the error of a nested struct already is.
+    pub fn locate(&self, error: Error) -> Error {
+        if error.to_string().starts_with(&format!("{}:", self.file)) {
+            error
+        } else {
+            Error::new(error.span(), format!("{}: {}", self, error))
+        }
+    }
+}
+
+/// The contents of a `gatt_server!(file = "gatt.toml")` file.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ServerFile {
+    connections: Option<i64>,
+    assigned_numbers: Option<String>,
+    #[serde(default)]
+    service: Vec<Entity>,
+}
+
+/// A service, characteristic, descriptor or attribute table, with the clauses of the macro
+/// syntax as keys.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Entity {
+    #[serde(rename = "type")]
+    type_name: Spanned<String>,
+    name: Option<String>,
+    uuid: Option<Scalar>,
+    properties: Option<Vec<String>>,
+    permissions: Option<Vec<String>>,
+    on_read: Option<String>,
+    on_write: Option<String>,
+    size: Option<i64>,
+    max: Option<i64>,
+    initial: Option<Scalar>,
+    #[serde(default)]
+    characteristic: Vec<Entity>,
+    #[serde(default)]
+    descriptor: Vec<Entity>,
+    #[serde(default)]
+    attribute: Vec<Entity>,
+}
+
+/// A UUID or initializer, which is either an integer or a string.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Scalar {
+    Int(i64),
+    Str(String),
+}
+
+impl Scalar {
+    /// The Rust literal the macro syntax has for the value.
+    fn to_rust(&self) -> String {
+        match self {
+            Scalar::Int(value) => value.to_string(),
+            Scalar::Str(value) => format!("{:?}", value),
+        }
+    }
+}
+
+/// Gives every token `span`, which points at the `file = "..."` clause.
+fn respan(tokens: TokenStream, span: Span) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|token| match token {
+            TokenTree::Group(group) => {
+                let mut respanned = Group::new(group.delimiter(), respan(group.stream(), span));
+                respanned.set_span(span);
+                TokenTree::Group(respanned)
+            }
+            mut token => {
+                token.set_span(span);
+                token
+            }
+        })
+        .collect()
+}
+
+/// Parses `text` in the macro syntax as if it was written at `span`.
+fn parse_at<T: Parse>(text: &str, span: Span) -> Result<T> {
+    let tokens = text
+        .parse::<TokenStream>()
+        .map_err(|_| Error::new(span, format!("Cannot parse `{}`", text)))?;
+    syn::parse2(respan(tokens, span))
+}
+
+struct Loader<'a> {
+    /// The path of the file as written in the macro.
+    name: String,
+    contents: &'a str,
+    span: Span,
+}
+
+impl Loader<'_> {
+    fn line(&self, offset: usize) -> usize {
+        self.contents[..offset].matches('\n').count() + 1
+    }
+
+    /// Builds the struct from the clauses of `entity`, written out in the macro syntax so they
+    /// are checked exactly like inline ones.
+    fn struct_like(&self, keyword: &str, entity: &Entity) -> Result<StructLike> {
+        let origin = Origin {
+            file: self.name.clone(),
+            line: self.line(entity.type_name.start()),
+        };
+        let mut text = keyword.to_string();
+        if let Some(name) = &entity.name {
+            text += &format!(" {}", name);
+        }
+        text += &format!(": {}", entity.type_name.get_ref());
+        if let Some(uuid) = &entity.uuid {
+            text += &format!(" uuid = {}", uuid.to_rust());
+        }
+        if let Some(properties) = &entity.properties {
+            text += &format!(" properties({})", properties.join(", "));
+        }
+        if let Some(permissions) = &entity.permissions {
+            text += &format!(" permissions({})", permissions.join(", "));
+        }
+        if let Some(on_read) = &entity.on_read {
+            text += &format!(" on_read = {}", on_read);
+        }
+        if let Some(on_write) = &entity.on_write {
+            text += &format!(" on_write = {}", on_write);
+        }
+        if let Some(initial) = &entity.initial {
+            text += &format!(" = {}", initial.to_rust());
+        }
+        let size = match (entity.size, entity.max) {
+            (Some(_), Some(_)) => {
+                return Err(origin.locate(Error::new(
+                    self.span,
+                    "An attribute has either a `size` or a `max`",
+                )))
+            }
+            (Some(size), None) => Some(size.to_string()),
+            (None, Some(max)) => Some(format!("max {}", max)),
+            (None, None) => None,
+        };
+        if let Some(size) = size {
+            if keyword != "attribute" {
+                return Err(origin.locate(Error::new(
+                    self.span,
+                    "Only attributes have a `size` or `max`",
+                )));
+            }
+            text += &format!(" {{ {} }}", size);
+        }
+
+        let mut struct_like =
+            parse_at::<StructLike>(&text, self.span).map_err(|e| origin.locate(e))?;
+        for (keyword, children) in [
+            ("attribute", &entity.attribute),
+            ("characteristic", &entity.characteristic),
+            ("descriptor", &entity.descriptor),
+        ] {
+            for child in children {
+                struct_like.children.push(self.struct_like(keyword, child)?);
+            }
+        }
+        struct_like.origin = Some(origin);
+        Ok(struct_like)
+    }
+}
+
+/// Loads the tree of `gatt_server!(file = "gatt.toml")` from a TOML file relative to the
+/// directory of the crate's `Cargo.toml`.
+pub fn load(file: &LitStr) -> Result<GattServerParsed> {
+    let name = file.value();
+    if Path::new(&name)
+        .extension()
+        .is_none_or(|extension| extension != "toml")
+    {
+        return Err(Error::new(file.span(), "Only `.toml` files can be loaded"));
+    }
+    let path = Path::new(&env::var_os("CARGO_MANIFEST_DIR").unwrap_or_default()).join(&name);
+    let contents = fs::read_to_string(&path).map_err(|error| {
+        Error::new(
+            file.span(),
+            format!("Failed to read `{}`: {}", path.display(), error),
+        )
+    })?;
+    let server = toml::from_str::<ServerFile>(&contents)
+        .map_err(|error| Error::new(file.span(), format!("{}: {}", name, error)))?;
+    let loader = Loader {
+        name,
+        contents: &contents,
+        span: file.span(),
+    };
+    let connections = server
+        .connections
+        .map(|connections| parse_at(&connections.to_string(), file.span()))
+        .transpose()?;
+    let assigned = server
+        .assigned_numbers
+        .map(|path| AssignedNumbers::load(&LitStr::new(&path, file.span())))
+        .transpose()?
+        .unwrap_or_default();
+    let struct_likes = server
+        .service
+        .iter()
+        .map(|service| loader.struct_like("service", service))
+        .collect::<Result<_>>()?;
+    Ok(GattServerParsed {
+        connections,
+        file: Some(path.display().to_string()),
+        assigned,
+        struct_likes,
+    })
+}
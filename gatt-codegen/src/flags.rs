@@ -0,0 +1,223 @@
+use syn::{
+    parenthesized,
+    parse::{Parse, ParseStream, Result},
+    punctuated::Punctuated,
+    Error, Ident, Token,
+};
+
+pub const PROPERTY_READ: u8 = 0x02;
+pub const PROPERTY_WRITE_WITHOUT_RESPONSE: u8 = 0x04;
+pub const PROPERTY_WRITE: u8 = 0x08;
+pub const PROPERTY_NOTIFY: u8 = 0x10;
+pub const PROPERTY_INDICATE: u8 = 0x20;
+pub const PROPERTY_AUTHENTICATED_SIGNED_WRITES: u8 = 0x40;
+
+/// All properties which allow the client to write the characteristic value.
+pub const PROPERTY_ANY_WRITE: u8 =
+    PROPERTY_WRITE | PROPERTY_WRITE_WITHOUT_RESPONSE | PROPERTY_AUTHENTICATED_SIGNED_WRITES;
+
+pub const PERMISSION_READ: u8 = 0x01;
+pub const PERMISSION_WRITE: u8 = 0x02;
+pub const PERMISSION_READ_ENCRYPTED: u8 = 0x04;
+pub const PERMISSION_READ_AUTHENTICATED: u8 = 0x08;
+pub const PERMISSION_READ_AUTHORIZED: u8 = 0x10;
+pub const PERMISSION_WRITE_ENCRYPTED: u8 = 0x20;
+pub const PERMISSION_WRITE_AUTHENTICATED: u8 = 0x40;
+pub const PERMISSION_WRITE_AUTHORIZED: u8 = 0x80;
+
+// The attribute table tells writes by request, by command and by signed command apart. The
+// `write` permission grants the ones the properties of a characteristic allow.
+pub const PERMISSION_WRITE_WITHOUT_RESPONSE: u16 = 0x100;
+pub const PERMISSION_SIGNED_WRITE: u16 = 0x200;
+
+/// All permissions which allow the client to write the attribute.
+pub const PERMISSION_ANY_WRITE: u16 =
+    PERMISSION_WRITE as u16 | PERMISSION_WRITE_WITHOUT_RESPONSE | PERMISSION_SIGNED_WRITE;
+
+/// The characteristic properties as they appear in the characteristic declaration.
+const PROPERTIES: &[(&str, u8)] = &[
+    ("read", PROPERTY_READ),
+    ("write_without_response", PROPERTY_WRITE_WITHOUT_RESPONSE),
+    ("write", PROPERTY_WRITE),
+    ("notify", PROPERTY_NOTIFY),
+    ("indicate", PROPERTY_INDICATE),
+    (
+        "authenticated_signed_writes",
+        PROPERTY_AUTHENTICATED_SIGNED_WRITES,
+    ),
+];
+
+/// The access and security requirements of an attribute.
+/// Every security requirement implies the matching access permission.
+const PERMISSIONS: &[(&str, u8)] = &[
+    ("read", PERMISSION_READ),
+    ("write", PERMISSION_WRITE),
+    (
+        "read_encrypted",
+        PERMISSION_READ | PERMISSION_READ_ENCRYPTED,
+    ),
+    (
+        "read_authenticated",
+        PERMISSION_READ | PERMISSION_READ_AUTHENTICATED,
+    ),
+    (
+        "read_authorized",
+        PERMISSION_READ | PERMISSION_READ_AUTHORIZED,
+    ),
+    (
+        "write_encrypted",
+        PERMISSION_WRITE | PERMISSION_WRITE_ENCRYPTED,
+    ),
+    (
+        "write_authenticated",
+        PERMISSION_WRITE | PERMISSION_WRITE_AUTHENTICATED,
+    ),
+    (
+        "write_authorized",
+        PERMISSION_WRITE | PERMISSION_WRITE_AUTHORIZED,
+    ),
+];
+
+/// The names of the flags all of whose bits are set in `bits`.
+fn names(known: &[(&'static str, u8)], bits: u8) -> Vec<&'static str> {
+    known
+        .iter()
+        .filter(|(_, flag)| bits & flag == *flag)
+        .map(|(name, _)| *name)
+        .collect()
+}
+
+/// The bits of the property `name`, as `properties(...)` takes it.
+pub fn property_bits(name: &Ident) -> u8 {
+    PROPERTIES
+        .iter()
+        .find(|(known, _)| name == known)
+        .map_or(0, |(_, bits)| *bits)
+}
+
+/// The names of the properties set in `bits`, as `properties(...)` takes them.
+pub fn property_names(bits: u8) -> Vec<&'static str> {
+    names(PROPERTIES, bits)
+}
+
+/// The names of the permissions granted by `bits`, as `permissions(...)` takes them, with
+/// `write` for Write Requests and the names of the properties for the other writes.
+pub fn permission_names(bits: u16) -> Vec<&'static str> {
+    let writable = if bits & PERMISSION_ANY_WRITE != 0 {
+        PERMISSION_WRITE
+    } else {
+        0
+    };
+    let mut names = names(PERMISSIONS, bits as u8 | writable);
+    if bits & PERMISSION_WRITE as u16 == 0 {
+        names.retain(|name| *name != "write");
+    }
+    if bits & PERMISSION_WRITE_WITHOUT_RESPONSE != 0 {
+        names.push("write_without_response");
+    }
+    if bits & PERMISSION_SIGNED_WRITE != 0 {
+        names.push("authenticated_signed_writes");
+    }
+    names
+}
+
+/// The write permissions matching the write properties of a characteristic.
+pub fn write_access(properties: u8) -> u16 {
+    [
+        (PROPERTY_WRITE, PERMISSION_WRITE as u16),
+        (
+            PROPERTY_WRITE_WITHOUT_RESPONSE,
+            PERMISSION_WRITE_WITHOUT_RESPONSE,
+        ),
+        (
+            PROPERTY_AUTHENTICATED_SIGNED_WRITES,
+            PERMISSION_SIGNED_WRITE,
+        ),
+    ]
+    .iter()
+    .filter(|(property, _)| properties & property != 0)
+    .fold(0, |access, (_, permission)| access | permission)
+}
+
+/// The permissions of the attribute table for the bits of a `permissions(...)` clause, where
+/// `write` grants the writes in `writes`.
+pub fn table_permissions(bits: u8, writes: u16) -> u16 {
+    let mut permissions = u16::from(bits & !PERMISSION_WRITE);
+    if bits & PERMISSION_WRITE != 0 {
+        permissions |= writes;
+    }
+    permissions
+}
+
+/// A parenthesized list of flags such as `(read, notify)`.
+#[derive(Debug)]
+pub struct Flags {
+    pub bits: u8,
+    pub idents: Vec<Ident>,
+}
+
+impl Flags {
+    fn parse_known(input: ParseStream, known: &[(&str, u8)], what: &str) -> Result<Self> {
+        let content;
+        parenthesized!(content in input);
+        let mut bits = 0;
+        let mut idents = Vec::<Ident>::new();
+        for ident in Punctuated::<Ident, Token![,]>::parse_terminated(&content)? {
+            let bit = known
+                .iter()
+                .find(|(name, _)| ident == name)
+                .map(|(_, bit)| *bit)
+                .ok_or_else(|| {
+                    let names = known.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+                    Error::new(
+                        ident.span(),
+                        format!(
+                            "Unknown {} `{}`, expected one of: {}",
+                            what,
+                            ident,
+                            names.join(", ")
+                        ),
+                    )
+                })?;
+            if idents.contains(&ident) {
+                return Err(Error::new(ident.span(), format!("Duplicate {}", what)));
+            }
+            bits |= bit;
+            idents.push(ident);
+        }
+        Ok(Flags { bits, idents })
+    }
+}
+
+/// The `properties(...)` clause of a characteristic.
+#[derive(Debug)]
+pub struct Properties(pub Flags);
+
+impl Parse for Properties {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let flags = Flags::parse_known(input, PROPERTIES, "property")?;
+        // The server has no signing keys, so it could only drop Signed Write Commands.
+        if let Some(ident) = flags
+            .idents
+            .iter()
+            .find(|i| property_bits(i) == PROPERTY_AUTHENTICATED_SIGNED_WRITES)
+        {
+            return Err(Error::new(
+                ident.span(),
+                "`authenticated_signed_writes` is not supported, the server cannot verify the \
+                 signature of Signed Write Commands",
+            ));
+        }
+        Ok(Properties(flags))
+    }
+}
+
+/// The `permissions(...)` clause of a characteristic, descriptor or attribute.
+#[derive(Debug)]
+pub struct Permissions(pub Flags);
+
+impl Parse for Permissions {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Flags::parse_known(input, PERMISSIONS, "permission").map(Permissions)
+    }
+}
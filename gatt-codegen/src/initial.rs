@@ -0,0 +1,113 @@
+use proc_macro2::Span;
+use syn::{
+    parse::{Parse, ParseStream, Result},
+    Error, Lit, LitInt, Token,
+};
+
+/// The `= ...` initializer of an attribute, which is baked into the store.
+#[derive(Debug)]
+pub struct InitialValue {
+    pub span: Span,
+    kind: InitialKind,
+}
+
+#[derive(Debug)]
+enum InitialKind {
+    /// A byte string or string literal.
+    Bytes(Vec<u8>),
+    /// An integer literal, possibly negated.
+    Int { lit: LitInt, negative: bool },
+}
+
+impl Parse for InitialValue {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let span = input.span();
+        let negative = input.parse::<Option<Token![-]>>()?.is_some();
+        let kind = match input.parse::<Lit>()? {
+            Lit::ByteStr(lit) if !negative => InitialKind::Bytes(lit.value()),
+            Lit::Str(lit) if !negative => InitialKind::Bytes(lit.value().into_bytes()),
+            Lit::Int(lit) => InitialKind::Int { lit, negative },
+            lit => {
+                return Err(Error::new(
+                    lit.span(),
+                    "Expected a byte string like `b\"Sensor-1\"`, a string or an integer like `0x0042u16`",
+                ))
+            }
+        };
+        Ok(InitialValue { span, kind })
+    }
+}
+
+/// The width in bytes and signedness of an integer type name.
+fn int_type(name: &str) -> Option<(usize, bool)> {
+    Some(match name {
+        "u8" => (1, false),
+        "u16" => (2, false),
+        "u32" => (4, false),
+        "u64" => (8, false),
+        "u128" => (16, false),
+        "i8" => (1, true),
+        "i16" => (2, true),
+        "i32" => (4, true),
+        "i64" => (8, true),
+        "i128" => (16, true),
+        _ => return None,
+    })
+}
+
+impl InitialValue {
+    /// Encodes the initializer little endian.
+    ///
+    /// `value_type` is the name of the attribute's value type, which gives unsuffixed integers
+    /// their width if it is an integer type. Attributes with a plain size pass `width` instead.
+    pub fn to_bytes(&self, value_type: Option<&str>, width: Option<usize>) -> Result<Vec<u8>> {
+        let (lit, negative) = match &self.kind {
+            InitialKind::Bytes(bytes) => return Ok(bytes.clone()),
+            InitialKind::Int { lit, negative } => (lit, *negative),
+        };
+        let (width, signed) = match (lit.suffix(), value_type.and_then(int_type), width) {
+            ("", Some(int), _) => int,
+            ("", None, Some(width)) => (width, false),
+            ("", None, None) => {
+                return Err(Error::new(
+                    self.span,
+                    "Add a type suffix such as `0x0042u16` to give the initializer a size",
+                ))
+            }
+            (suffix, ..) => int_type(suffix).ok_or_else(|| {
+                Error::new(
+                    lit.span(),
+                    format!("Unsupported integer suffix `{}`", suffix),
+                )
+            })?,
+        };
+        let magnitude = lit.base10_parse::<u128>()?;
+        let out_of_range = || {
+            Error::new(
+                self.span,
+                format!("The initializer is out of range for a {}-byte value", width),
+            )
+        };
+        if width == 0 {
+            return Err(out_of_range());
+        }
+        let bits = (width * 8).min(128) as u32;
+        let value = if negative {
+            if !signed || magnitude > 1 << (bits - 1) {
+                return Err(out_of_range());
+            }
+            (magnitude as i128).wrapping_neg() as u128
+        } else {
+            let max = if signed {
+                (1u128 << (bits - 1)) - 1
+            } else {
+                u128::MAX >> (128 - bits)
+            };
+            if magnitude > max {
+                return Err(out_of_range());
+            }
+            magnitude
+        };
+        Ok(value.to_le_bytes()[..width.min(16)].to_vec())
+    }
+}
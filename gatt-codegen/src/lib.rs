@@ -0,0 +1,1825 @@
+//! The parser, layout and code generation behind the `gatt` macros, shared with `gatt-build` so
+//! build scripts can describe a database without expanding them.
+
+use std::ops::Range;
+
+mod client;
+mod derive;
+mod export;
+mod file;
+mod flags;
+mod initial;
+mod sig;
+mod uuid;
+mod validate;
+
+use flags::{Permissions, Properties};
+use heck::SnekCase;
+use initial::InitialValue;
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned};
+use syn::{
+    braced,
+    parse::{Parse, ParseStream, Result},
+    parse_quote,
+    punctuated::Punctuated,
+    spanned::Spanned,
+    token, DeriveInput, Error, GenericArgument, Ident, LitInt, LitStr, Path, PathArguments, Token,
+    Type,
+};
+use uuid::Uuid;
+
+pub use export::describe_file;
+
+/// Attribute type of a primary service declaration.
+const PRIMARY_SERVICE: u16 = 0x2800;
+/// Attribute type of a characteristic declaration.
+const CHARACTERISTIC: u16 = 0x2803;
+/// Attribute type of a client characteristic configuration descriptor.
+const CLIENT_CHARACTERISTIC_CONFIGURATION: u16 = 0x2902;
+/// The longest attribute value ATT allows.
+const MAX_ATTRIBUTE_LENGTH: usize = 512;
+
+#[derive(Debug)]
+struct GattServerParsed {
+    /// The `connections = ...` clause, how many clients are served at the same time.
+    connections: Option<LitInt>,
+    /// The path of the file the tree was loaded from, which the generated code includes so it is
+    /// rebuilt when the file changes.
+    file: Option<String>,
+    /// The assigned numbers of an `assigned_numbers = "..."` clause, which names are looked up in.
+    assigned: sig::AssignedNumbers,
+    struct_likes: Vec<StructLike>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Kind {
+    Service,
+    Characteristic,
+    Descriptor,
+    Attribute,
+}
+
+mod kw {
+    syn::custom_keyword!(service);
+    syn::custom_keyword!(characteristic);
+    syn::custom_keyword!(descriptor);
+    syn::custom_keyword!(attribute);
+    syn::custom_keyword!(uuid);
+    syn::custom_keyword!(properties);
+    syn::custom_keyword!(permissions);
+    syn::custom_keyword!(max);
+    syn::custom_keyword!(on_read);
+    syn::custom_keyword!(on_write);
+    syn::custom_keyword!(connections);
+    syn::custom_keyword!(assigned_numbers);
+    syn::custom_keyword!(file);
+}
+
+impl Parse for Kind {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(kw::service) {
+            input.parse::<Ident>()?;
+            Ok(Kind::Service)
+        } else if input.peek(kw::characteristic) {
+            input.parse::<Ident>()?;
+            Ok(Kind::Characteristic)
+        } else if input.peek(kw::descriptor) {
+            input.parse::<Ident>()?;
+            Ok(Kind::Descriptor)
+        } else if input.peek(kw::attribute) {
+            input.parse::<Ident>()?;
+            Ok(Kind::Attribute)
+        } else {
+            Err(input.error("Expected a valid path segment"))
+        }
+    }
+}
+
+#[derive(Debug)]
+struct StructLike {
+    kind: Kind,
+    /// The span of the `service`, `characteristic`, `descriptor` or `attribute` keyword.
+    span: Span,
+    name: Option<Ident>,
+    type_name: Path,
+    uuid: Option<Uuid>,
+    properties: Option<Properties>,
+    permissions: Option<Permissions>,
+    /// The `on_read = ...` callback of a characteristic.
+    on_read: Option<Path>,
+    /// The `on_write = ...` callback of a characteristic.
+    on_write: Option<Path>,
+    children: Vec<StructLike>,
+    size: Option<LitInt>,
+    /// Whether `size` is the `max` of a variable length value.
+    variable: bool,
+    initial: Option<InitialValue>,
+    /// Where the struct was declared if it was loaded from a file.
+    origin: Option<file::Origin>,
+}
+
+impl StructLike {
+    /// Names the file and line of structs loaded from a file in `error`.
+    fn locate(&self, error: Error) -> Error {
+        match &self.origin {
+            Some(origin) => origin.locate(error),
+            None => error,
+        }
+    }
+}
+
+impl Parse for StructLike {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let span = input.span();
+        let kind = input.parse()?;
+        let name = if input.peek(Ident) {
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        input.parse::<Token![:]>()?;
+        let type_name = input.parse()?;
+        let mut uuid = None;
+        let mut properties = None;
+        let mut permissions = None;
+        let mut on_read = None;
+        let mut on_write = None;
+        let mut initial = None;
+        loop {
+            if input.peek(kw::uuid) {
+                let keyword = input.parse::<kw::uuid>()?;
+                if uuid.is_some() {
+                    return Err(Error::new(keyword.span, "Duplicate `uuid` clause"));
+                }
+                input.parse::<Token![=]>()?;
+                uuid = Some(input.parse()?);
+            } else if input.peek(kw::properties) {
+                let keyword = input.parse::<kw::properties>()?;
+                if !matches!(kind, Kind::Characteristic) {
+                    return Err(Error::new(
+                        keyword.span,
+                        "Only characteristics have properties",
+                    ));
+                }
+                if properties.is_some() {
+                    return Err(Error::new(keyword.span, "Duplicate `properties` clause"));
+                }
+                properties = Some(input.parse()?);
+            } else if input.peek(kw::permissions) {
+                let keyword = input.parse::<kw::permissions>()?;
+                if matches!(kind, Kind::Service) {
+                    return Err(Error::new(keyword.span, "Services cannot have permissions"));
+                }
+                if permissions.is_some() {
+                    return Err(Error::new(keyword.span, "Duplicate `permissions` clause"));
+                }
+                permissions = Some(input.parse()?);
+            } else if input.peek(kw::on_read) || input.peek(kw::on_write) {
+                let keyword = input.parse::<Ident>()?;
+                if !matches!(kind, Kind::Characteristic) {
+                    return Err(Error::new(
+                        keyword.span(),
+                        "Only characteristics have read and write callbacks",
+                    ));
+                }
+                let callback = if keyword == "on_read" {
+                    &mut on_read
+                } else {
+                    &mut on_write
+                };
+                if callback.is_some() {
+                    return Err(Error::new(
+                        keyword.span(),
+                        format!("Duplicate `{}` clause", keyword),
+                    ));
+                }
+                input.parse::<Token![=]>()?;
+                *callback = Some(input.parse()?);
+            } else if input.peek(Token![=]) {
+                let eq = input.parse::<Token![=]>()?;
+                if !matches!(kind, Kind::Attribute) {
+                    return Err(Error::new(eq.span, "Only attributes have initial values"));
+                }
+                if initial.is_some() {
+                    return Err(Error::new(eq.span, "Duplicate initial value"));
+                }
+                initial = Some(input.parse()?);
+            } else {
+                break;
+            }
+        }
+        let mut children = vec![];
+        let mut size = None;
+        let mut variable = false;
+        if input.peek(token::Brace) {
+            let content;
+            braced!(content in input);
+            // Children of attributes are parsed anyway, so `validate` can point at them.
+            if matches!(kind, Kind::Attribute) && (!content.peek(Ident) || content.peek(kw::max)) {
+                variable = content.parse::<Option<kw::max>>()?.is_some();
+                size = Some(content.parse()?);
+            } else {
+                children = Punctuated::<StructLike, Token![,]>::parse_terminated(&content)?
+                    .into_iter()
+                    .collect()
+            }
+        }
+        // The initializer may also follow the size, as in `{ 4 } = b"1.2"`.
+        if matches!(kind, Kind::Attribute) && initial.is_none() && input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            initial = Some(input.parse()?);
+        }
+        Ok(StructLike {
+            kind,
+            span,
+            name,
+            type_name,
+            uuid,
+            properties,
+            permissions,
+            on_read,
+            on_write,
+            children,
+            size,
+            variable,
+            initial,
+            origin: None,
+        })
+    }
+}
+
+impl Parse for GattServerParsed {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(kw::file) {
+            input.parse::<kw::file>()?;
+            input.parse::<Token![=]>()?;
+            let file = input.parse::<LitStr>()?;
+            input.parse::<Option<Token![,]>>()?;
+            if !input.is_empty() {
+                return Err(input.error("A tree loaded from a file cannot be extended inline"));
+            }
+            return file::load(&file);
+        }
+        let mut connections = None;
+        let mut assigned = None;
+        // The clauses concerning the whole server lead the tree.
+        loop {
+            if input.peek(kw::connections) {
+                let keyword = input.parse::<kw::connections>()?;
+                if connections.is_some() {
+                    return Err(Error::new(keyword.span, "Duplicate `connections` clause"));
+                }
+                input.parse::<Token![=]>()?;
+                connections = Some(input.parse()?);
+            } else if input.peek(kw::assigned_numbers) {
+                let keyword = input.parse::<kw::assigned_numbers>()?;
+                if assigned.is_some() {
+                    return Err(Error::new(
+                        keyword.span,
+                        "Duplicate `assigned_numbers` clause",
+                    ));
+                }
+                input.parse::<Token![=]>()?;
+                assigned = Some(sig::AssignedNumbers::load(&input.parse()?)?);
+            } else {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+        Ok(GattServerParsed {
+            connections,
+            file: None,
+            assigned: assigned.unwrap_or_default(),
+            struct_likes: Punctuated::<StructLike, Token![,]>::parse_terminated(input)?
+                .into_iter()
+                .collect(),
+        })
+    }
+}
+
+#[derive(Debug)]
+struct Service {
+    uuid: Uuid,
+    attributes: Range<usize>,
+    characteristics: Range<usize>,
+    name: Option<Ident>,
+    type_name: Path,
+}
+
+#[derive(Debug)]
+struct Characteristic {
+    uuid: Uuid,
+    properties: u8,
+    attributes: Range<usize>,
+    descriptors: Range<usize>,
+    name: Option<Ident>,
+    type_name: Path,
+}
+
+#[derive(Debug)]
+struct Descriptor {
+    uuid: Uuid,
+    attributes: Range<usize>,
+    name: Option<Ident>,
+    /// The user type of the descriptor, `None` for synthesized descriptors.
+    type_name: Option<Path>,
+}
+
+#[derive(Debug)]
+enum AttributeKind {
+    /// A primary service declaration synthesized for every `service`, its value is the service UUID.
+    ServiceDeclaration(Uuid),
+    /// A characteristic declaration synthesized for every `characteristic`.
+    CharacteristicDeclaration {
+        properties: u8,
+        value_handle: u16,
+        uuid: Uuid,
+    },
+    /// The value of a client characteristic configuration descriptor synthesized for every
+    /// characteristic which notifies or indicates.
+    ClientCharacteristicConfiguration,
+    /// A user declared `attribute` whose value lives in `DATA_STORE`.
+    Value {
+        /// The Rust type the value is encoded as, `[u8; N]` for attributes with a plain size.
+        value_type: Box<Type>,
+        /// Whether the value holds up to the `N` bytes of its `[u8; N]` type rather than all of
+        /// them, for attributes declared with `{ max N }`.
+        variable: bool,
+        /// Whether every connection has its own copy of the value, as for client characteristic
+        /// configurations.
+        per_connection: bool,
+        /// The encoded `= ...` initializer and its span.
+        initial: Option<(Vec<u8>, Span)>,
+        /// The `on_read` callback of the characteristic this is the value of.
+        on_read: Option<Path>,
+        /// The `on_write` callback of the characteristic this is the value of.
+        on_write: Option<Path>,
+        name: Option<Ident>,
+        type_name: Path,
+    },
+}
+
+impl AttributeKind {
+    /// The constant value of a declaration.
+    fn declaration_value(&self) -> Option<Vec<u8>> {
+        match self {
+            AttributeKind::ServiceDeclaration(uuid) => Some(uuid.to_bytes()),
+            AttributeKind::CharacteristicDeclaration {
+                properties,
+                value_handle,
+                uuid,
+            } => {
+                let mut value = vec![*properties];
+                value.extend_from_slice(&value_handle.to_le_bytes());
+                value.extend(uuid.to_bytes());
+                Some(value)
+            }
+            AttributeKind::ClientCharacteristicConfiguration | AttributeKind::Value { .. } => None,
+        }
+    }
+
+    /// Whether every connection has its own copy of the value.
+    fn per_connection(&self) -> bool {
+        matches!(
+            self,
+            AttributeKind::ClientCharacteristicConfiguration
+                | AttributeKind::Value {
+                    per_connection: true,
+                    ..
+                }
+        )
+    }
+
+    /// A constant expression for the number of bytes the attribute occupies in `DATA_STORE`, per
+    /// connection for per connection values, `None` for declarations which have a constant value.
+    fn store_size(&self) -> Option<TokenStream> {
+        match self {
+            AttributeKind::ServiceDeclaration(_)
+            | AttributeKind::CharacteristicDeclaration { .. } => None,
+            AttributeKind::ClientCharacteristicConfiguration => Some(quote! { 2usize }),
+            // Variable length values keep their current length in front of them.
+            AttributeKind::Value {
+                value_type,
+                variable: true,
+                ..
+            } => Some(quote! { 2usize + <#value_type as ::gatt_runtime::GattValue>::SIZE }),
+            AttributeKind::Value { value_type, .. } => {
+                Some(quote! { <#value_type as ::gatt_runtime::GattValue>::SIZE })
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Attribute {
+    /// The ATT handle; always the index into `ATTRIBUTES` plus one.
+    handle: u16,
+    /// The type of the attribute, EG "Primary Service" or the UUID of the owning characteristic.
+    att_type: Uuid,
+    /// The access and security requirements, see the `flags` module.
+    permissions: u16,
+    kind: AttributeKind,
+}
+
+#[derive(Debug)]
+struct GattServer {
+    services: Vec<Service>,
+    characteristics: Vec<Characteristic>,
+    descriptors: Vec<Descriptor>,
+    attributes: Vec<Attribute>,
+    /// The assigned numbers the names of the tree are looked up in besides the tables of `sig`.
+    assigned: sig::AssignedNumbers,
+}
+
+impl GattServer {
+    /// Appends an attribute to the table, assigning it the next free handle.
+    fn push_attribute(
+        &mut self,
+        att_type: Uuid,
+        permissions: u16,
+        kind: AttributeKind,
+    ) -> Result<()> {
+        let handle = self.attributes.len() + 1;
+        if handle > u16::MAX as usize {
+            return Err(Error::new(
+                Span::call_site(),
+                "The GATT server exceeds the 65535 available attribute handles",
+            ));
+        }
+        self.attributes.push(Attribute {
+            handle: handle as u16,
+            att_type,
+            permissions,
+            kind,
+        });
+        Ok(())
+    }
+}
+
+/// What the attributes of a characteristic or descriptor inherit from it.
+#[derive(Debug, Clone, Copy)]
+struct Inherited {
+    uuid: Uuid,
+    permissions: u16,
+    /// The writes a `write` permission of the attributes grants, see `flags::table_permissions`.
+    writes: u16,
+    /// The specification of the characteristic, for its value attribute.
+    spec: Option<&'static sig::Characteristic>,
+}
+
+/// The UUID of a service, characteristic or descriptor, which must be given unless the SIG
+/// assigned one to its type name.
+fn required_uuid(server: &GattServer, input: &StructLike) -> Result<Uuid> {
+    sig::uuid(&server.assigned, input).ok_or_else(|| sig::missing(&server.assigned, input))
+}
+
+/// Determines the permissions of a characteristic value, checking them against its properties.
+///
+/// Each write property grants its own kind of write, so a client can only use the ones the
+/// characteristic declaration advertises.
+fn characteristic_permissions(input: &StructLike, properties: u8) -> Result<u16> {
+    let writes = flags::write_access(properties);
+    let permissions = match &input.permissions {
+        Some(permissions) => permissions,
+        None => {
+            let mut permissions = writes;
+            if properties & flags::PROPERTY_READ != 0 {
+                permissions |= u16::from(flags::PERMISSION_READ);
+            }
+            return Ok(permissions);
+        }
+    };
+    let checks = [
+        (
+            "read",
+            properties & flags::PROPERTY_READ != 0,
+            permissions.0.bits & flags::PERMISSION_READ != 0,
+        ),
+        (
+            "write",
+            properties & flags::PROPERTY_ANY_WRITE != 0,
+            permissions.0.bits & flags::PERMISSION_WRITE != 0,
+        ),
+    ];
+    for (access, property, permission) in checks.iter() {
+        if *permission && !*property {
+            let ident = permissions
+                .0
+                .idents
+                .iter()
+                .find(|i| i.to_string().starts_with(access))
+                .unwrap();
+            return Err(Error::new(
+                ident.span(),
+                format!(
+                    "`{}` needs a {} property on the characteristic",
+                    ident, access
+                ),
+            ));
+        }
+        if *property && !*permission {
+            return Err(Error::new_spanned(
+                &input.type_name,
+                format!(
+                    "The characteristic has a {} property but no {} permission",
+                    access, access
+                ),
+            ));
+        }
+    }
+    Ok(flags::table_permissions(permissions.0.bits, writes))
+}
+
+/// Walks the parsed tree in declaration order, so the attribute table ends up sorted by handle:
+/// service declaration, characteristic declaration, value and then the descriptors.
+///
+/// `inherited` holds the type and permissions of attributes which do not declare their own.
+fn recurse_structs(
+    server: &mut GattServer,
+    input: &StructLike,
+    inherited: Option<Inherited>,
+) -> Result<()> {
+    lay_out_struct(server, input, inherited).map_err(|e| input.locate(e))
+}
+
+fn lay_out_struct(
+    server: &mut GattServer,
+    input: &StructLike,
+    inherited: Option<Inherited>,
+) -> Result<()> {
+    match input.kind {
+        Kind::Service => {
+            let mut characteristics = vec![];
+            let mut attributes = vec![];
+            for child in &input.children {
+                match child.kind {
+                    Kind::Characteristic => characteristics.push(child),
+                    Kind::Attribute => attributes.push(child),
+                    // Rejected by `validate`.
+                    _ => (),
+                }
+            }
+            let uuid = required_uuid(server, input)?;
+            let ac = server.attributes.len();
+            server.push_attribute(
+                Uuid::Uuid16(PRIMARY_SERVICE),
+                flags::PERMISSION_READ.into(),
+                AttributeKind::ServiceDeclaration(uuid),
+            )?;
+            for a in &attributes {
+                recurse_structs(server, a, None)?;
+            }
+            let ae = server.attributes.len();
+            let cc = server.characteristics.len();
+            for c in &characteristics {
+                recurse_structs(server, c, None)?;
+            }
+            server.services.push(Service {
+                uuid,
+                attributes: ac..ae,
+                characteristics: cc..cc + characteristics.len(),
+                name: input.name.clone(),
+                type_name: input.type_name.clone(),
+            });
+        }
+        Kind::Characteristic => {
+            let spec = sig::characteristic(&server.assigned, input);
+            let value = spec.and_then(|spec| spec.value_attribute(input));
+            let mut descriptors = vec![];
+            let mut attributes = vec![];
+            for child in &input.children {
+                match child.kind {
+                    Kind::Descriptor => descriptors.push(child),
+                    Kind::Attribute => attributes.push(child),
+                    // Rejected by `validate`.
+                    _ => (),
+                }
+            }
+            // Characteristics the SIG specified get their value attribute generated.
+            if attributes.is_empty() {
+                attributes.extend(&value);
+            }
+            let uuid = required_uuid(server, input)?;
+            let properties = match (&input.properties, spec) {
+                (Some(properties), Some(spec)) => {
+                    spec.check_properties(input, properties)?;
+                    properties.0.bits
+                }
+                (Some(properties), None) => properties.0.bits,
+                (None, Some(spec)) => spec.mandatory,
+                (None, None) => flags::PROPERTY_READ,
+            };
+            if let Some(spec) = spec {
+                spec.check_descriptors(&server.assigned, input, &descriptors)?;
+            }
+            let permissions = characteristic_permissions(input, properties)?;
+            let subscribable =
+                properties & (flags::PROPERTY_NOTIFY | flags::PROPERTY_INDICATE) != 0;
+            let cccd = descriptors.iter().find(|d| {
+                sig::uuid(&server.assigned, d)
+                    == Some(Uuid::Uuid16(CLIENT_CHARACTERISTIC_CONFIGURATION))
+            });
+            if let (Some(cccd), false) = (cccd, subscribable) {
+                return Err(Error::new_spanned(
+                    &cccd.type_name,
+                    "Client characteristic configuration descriptors need the notify or indicate property",
+                ));
+            }
+            if attributes.is_empty() {
+                return Err(Error::new_spanned(
+                    &input.type_name,
+                    "Characteristics need an attribute holding their value",
+                ));
+            }
+
+            let ac = server.attributes.len();
+            server.push_attribute(
+                Uuid::Uuid16(CHARACTERISTIC),
+                flags::PERMISSION_READ.into(),
+                AttributeKind::CharacteristicDeclaration {
+                    properties,
+                    // The value attribute directly follows the declaration.
+                    value_handle: server.attributes.len() as u16 + 2,
+                    uuid,
+                },
+            )?;
+            for (i, a) in attributes.iter().enumerate() {
+                let inherited = Inherited {
+                    uuid,
+                    permissions,
+                    writes: match flags::write_access(properties) {
+                        0 => flags::PERMISSION_WRITE.into(),
+                        writes => writes,
+                    },
+                    // Only the first attribute is the value.
+                    spec: spec.filter(|_| i == 0),
+                };
+                recurse_structs(server, a, Some(inherited))?;
+            }
+            // The callbacks of the characteristic act on its value.
+            if let AttributeKind::Value {
+                on_read, on_write, ..
+            } = &mut server.attributes[ac + 1].kind
+            {
+                *on_read = input.on_read.clone();
+                *on_write = input.on_write.clone();
+            }
+            let ae = server.attributes.len();
+            let dc = server.descriptors.len();
+            if subscribable && cccd.is_none() {
+                let attribute = server.attributes.len();
+                server.push_attribute(
+                    Uuid::Uuid16(CLIENT_CHARACTERISTIC_CONFIGURATION),
+                    (flags::PERMISSION_READ | flags::PERMISSION_WRITE).into(),
+                    AttributeKind::ClientCharacteristicConfiguration,
+                )?;
+                server.descriptors.push(Descriptor {
+                    uuid: Uuid::Uuid16(CLIENT_CHARACTERISTIC_CONFIGURATION),
+                    attributes: attribute..attribute + 1,
+                    name: None,
+                    type_name: None,
+                });
+            }
+            for d in &descriptors {
+                recurse_structs(server, d, None)?;
+            }
+            server.characteristics.push(Characteristic {
+                uuid,
+                properties,
+                attributes: ac..ae,
+                descriptors: dc..server.descriptors.len(),
+                name: input.name.clone(),
+                type_name: input.type_name.clone(),
+            });
+        }
+        Kind::Descriptor => {
+            let mut attributes = vec![];
+            for child in &input.children {
+                if let Kind::Attribute = child.kind {
+                    attributes.push(child)
+                }
+            }
+            let uuid = required_uuid(server, input)?;
+            if attributes.is_empty() {
+                return Err(Error::new_spanned(
+                    &input.type_name,
+                    "Descriptors need an attribute holding their value",
+                ));
+            }
+            let permissions = match &input.permissions {
+                Some(permissions) => permissions.0.bits,
+                // Clients subscribe by writing the client characteristic configuration.
+                None if uuid == Uuid::Uuid16(CLIENT_CHARACTERISTIC_CONFIGURATION) => {
+                    flags::PERMISSION_READ | flags::PERMISSION_WRITE
+                }
+                None => flags::PERMISSION_READ,
+            };
+            // Descriptors are only written with Write Requests.
+            let writes = flags::PERMISSION_WRITE.into();
+            let permissions = flags::table_permissions(permissions, writes);
+            let ac = server.attributes.len();
+            for a in &attributes {
+                let inherited = Inherited {
+                    uuid,
+                    permissions,
+                    writes,
+                    spec: None,
+                };
+                recurse_structs(server, a, Some(inherited))?;
+            }
+            server.descriptors.push(Descriptor {
+                uuid,
+                attributes: ac..ac + attributes.len(),
+                name: input.name.clone(),
+                type_name: Some(input.type_name.clone()),
+            });
+        }
+        Kind::Attribute => {
+            let att_type = input
+                .uuid
+                .or_else(|| inherited.map(|i| i.uuid))
+                .ok_or_else(|| {
+                    Error::new_spanned(
+                        &input.type_name,
+                        "Attributes outside of a characteristic or descriptor need a `uuid = ...` clause",
+                    )
+                })?;
+            let permissions = input
+                .permissions
+                .as_ref()
+                .map(|p| {
+                    let writes = inherited.map_or(flags::PERMISSION_WRITE.into(), |i| i.writes);
+                    flags::table_permissions(p.0.bits, writes)
+                })
+                .or_else(|| inherited.map(|i| i.permissions))
+                .unwrap_or(flags::PERMISSION_READ.into());
+            let per_connection = att_type == Uuid::Uuid16(CLIENT_CHARACTERISTIC_CONFIGURATION);
+            if per_connection {
+                if let Some(initial) = &input.initial {
+                    return Err(Error::new(
+                        initial.span,
+                        "Client characteristic configurations start out cleared and take no initializer",
+                    ));
+                }
+                if input.variable {
+                    return Err(Error::new_spanned(
+                        &input.size,
+                        "Client characteristic configurations have a fixed size",
+                    ));
+                }
+            }
+            let (type_name, value_type) = split_value_type(&input.type_name)?;
+            let (value_type, spec_size) = match inherited.and_then(|i| i.spec) {
+                Some(spec) => spec.value_format(input, value_type)?,
+                None => (value_type, None),
+            };
+            let (value_type, initial) = match (value_type, &input.size) {
+                (Some(value_type), None) => {
+                    let type_name = match &value_type {
+                        Type::Path(path) => path.path.get_ident().map(|i| i.to_string()),
+                        _ => None,
+                    };
+                    let initial = match &input.initial {
+                        Some(i) => Some((i.to_bytes(type_name.as_deref(), None)?, i.span)),
+                        None => None,
+                    };
+                    (value_type, initial)
+                }
+                (Some(_), Some(size)) => {
+                    return Err(Error::new(
+                        size.span(),
+                        "Typed attributes take their size from the value type",
+                    ))
+                }
+                (None, size) => {
+                    let size = size
+                        .as_ref()
+                        .map(|s| s.base10_parse::<usize>())
+                        .transpose()?
+                        .or(spec_size);
+                    let initial = match &input.initial {
+                        Some(i) => {
+                            // Unsuffixed integers cannot tell how many bytes they occupy.
+                            let width = if input.variable { None } else { size };
+                            let mut bytes = i.to_bytes(None, width)?;
+                            let size = size.unwrap_or(bytes.len());
+                            if bytes.len() > size {
+                                return Err(Error::new(
+                                    i.span,
+                                    format!(
+                                        "The initializer has {} bytes but the attribute only holds {}",
+                                        bytes.len(),
+                                        size
+                                    ),
+                                ));
+                            }
+                            if input.variable {
+                                let len = (bytes.len() as u16).to_le_bytes();
+                                bytes.splice(0..0, len.iter().copied());
+                            } else {
+                                bytes.resize(size, 0);
+                            }
+                            Some((bytes, i.span))
+                        }
+                        None => None,
+                    };
+                    // Without a size the initializer determines it.
+                    let size = size.unwrap_or_else(|| initial.as_ref().map_or(0, |i| i.0.len()));
+                    if input.variable && size > MAX_ATTRIBUTE_LENGTH {
+                        return Err(Error::new(
+                            input.size.as_ref().unwrap().span(),
+                            format!(
+                                "Attribute values hold at most {} bytes",
+                                MAX_ATTRIBUTE_LENGTH
+                            ),
+                        ));
+                    }
+                    (parse_quote! { [u8; #size] }, initial)
+                }
+            };
+            // Attributes sharing a type share its handle type, whose accessors are typed after
+            // the value, and its `GattEvent` variant, which could not tell the attributes apart.
+            let writable = permissions & flags::PERMISSION_ANY_WRITE != 0;
+            for other in &server.attributes {
+                match &other.kind {
+                    AttributeKind::Value {
+                        type_name: other_type,
+                        value_type: other_value,
+                        variable,
+                        per_connection: other_per_connection,
+                        ..
+                    } if *other_type == type_name => {
+                        if writable || other.permissions & flags::PERMISSION_ANY_WRITE != 0 {
+                            return Err(Error::new_spanned(
+                                &type_name,
+                                "Attributes clients may write need a type of their own, which names their `GattEvent` variant",
+                            ));
+                        }
+                        if **other_value != value_type
+                            || *variable != input.variable
+                            || *other_per_connection != per_connection
+                        {
+                            return Err(Error::new_spanned(
+                                &type_name,
+                                "Attributes sharing a type need the same value type or size",
+                            ));
+                        }
+                    }
+                    _ => (),
+                }
+            }
+            server.push_attribute(
+                att_type,
+                permissions,
+                AttributeKind::Value {
+                    value_type: Box::new(value_type),
+                    variable: input.variable,
+                    per_connection,
+                    initial,
+                    on_read: None,
+                    on_write: None,
+                    name: input.name.clone(),
+                    type_name,
+                },
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Splits `Temperature<i16>` into the attribute type `Temperature` and the value type `i16`.
+fn split_value_type(type_name: &Path) -> Result<(Path, Option<Type>)> {
+    let mut attribute_type = type_name.clone();
+    let segment = attribute_type.segments.last_mut().unwrap();
+    let arguments = std::mem::replace(&mut segment.arguments, PathArguments::None);
+    match arguments {
+        PathArguments::None => Ok((attribute_type, None)),
+        PathArguments::AngleBracketed(arguments) if arguments.args.len() == 1 => {
+            match arguments.args.first() {
+                Some(GenericArgument::Type(value_type)) => {
+                    Ok((attribute_type, Some(value_type.clone())))
+                }
+                _ => Err(Error::new_spanned(
+                    &arguments,
+                    "Expected the value type of the attribute, such as `Temperature<i16>`",
+                )),
+            }
+        }
+        arguments => Err(Error::new_spanned(
+            arguments,
+            "Expected the value type of the attribute, such as `Temperature<i16>`",
+        )),
+    }
+}
+
+/// A constant expression for `&table[range]`, which cannot be written directly in a static.
+fn sub_slice(table: TokenStream, range: &Range<usize>) -> TokenStream {
+    let start = range.start;
+    let len = range.len();
+    quote! { #table.split_at(#start).1.split_at(#len).0 }
+}
+
+/// Accessors on a service, characteristic or descriptor handle for its value attributes.
+fn attribute_accessors(attributes: &[Attribute]) -> Vec<TokenStream> {
+    attributes
+        .iter()
+        .filter_map(|a| match &a.kind {
+            AttributeKind::Value {
+                name, type_name, ..
+            } => {
+                let fn_name = accessor_name(name, type_name);
+                let handle_type_name = handle_path(type_name);
+                let index = a.handle as usize - 1;
+                Some(quote! {
+                    pub fn #fn_name(&mut self) -> #handle_type_name {
+                        #handle_type_name {
+                            inner: &ATTRIBUTES[#index],
+                            pd: core::marker::PhantomData {}
+                        }
+                    }
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// `notify` and `indicate` on a characteristic handle for the properties it has.
+fn value_updates(server: &GattServer, characteristic: &Characteristic) -> TokenStream {
+    let cccd = server.descriptors[characteristic.descriptors.clone()]
+        .iter()
+        .find(|d| d.uuid == Uuid::Uuid16(CLIENT_CHARACTERISTIC_CONFIGURATION))
+        .filter(|d| !d.attributes.is_empty())
+        .map(|d| d.attributes.start);
+    let value = &server.attributes[characteristic.attributes.start + 1];
+    let (cccd, (type_name, value_type, variable)) = match (cccd, &value.kind) {
+        (
+            Some(cccd),
+            AttributeKind::Value {
+                type_name,
+                value_type,
+                variable,
+                ..
+            },
+        ) => (cccd, (type_name, value_type, *variable)),
+        _ => return quote! {},
+    };
+    let index = value.handle as usize - 1;
+    let handle = value.handle;
+    let handle_type_name = handle_path(type_name);
+    let (parameter, set) = if variable {
+        (
+            quote! { &[u8] },
+            quote! {
+                attribute
+                    .set(value)
+                    .map_err(|_| ::gatt_runtime::att::NotifyError::ValueTooLong)?;
+            },
+        )
+    } else {
+        (quote! { #value_type }, quote! { attribute.set(value); })
+    };
+    let store = quote! {
+        let mut attribute = #handle_type_name {
+            inner: &ATTRIBUTES[#index],
+            pd: core::marker::PhantomData {}
+        };
+        #set
+        let (value, configuration) =
+            unsafe {
+                (
+                    ATTRIBUTES[#index].value.bytes(),
+                    // A connection the server keeps no configuration for has not subscribed.
+                    ATTRIBUTES[#cccd].value.of(conn.connection()).map_or(&[][..], |c| c.bytes()),
+                )
+            };
+    };
+
+    let mut updates = quote! {};
+    if characteristic.properties & flags::PROPERTY_NOTIFY != 0 {
+        updates.extend(quote! {
+            /// Stores `value` and builds a Handle Value Notification of it in `pdu` if the client
+            /// of `conn` subscribed to notifications. Returns the length of the PDU.
+            pub fn notify(
+                &mut self,
+                conn: &::gatt_runtime::att::AttServer,
+                value: #parameter,
+                pdu: &mut [u8],
+            ) -> Result<usize, ::gatt_runtime::att::NotifyError> {
+                #store
+                conn.notification(#handle, value, configuration, pdu)
+            }
+        });
+    }
+    if characteristic.properties & flags::PROPERTY_INDICATE != 0 {
+        updates.extend(quote! {
+            /// Stores `value` and builds a Handle Value Indication of it in `pdu` if the client of
+            /// `conn` subscribed to indications and confirmed the previous one. Returns the length
+            /// of the PDU.
+            pub fn indicate(
+                &mut self,
+                conn: &mut ::gatt_runtime::att::AttServer,
+                value: #parameter,
+                pdu: &mut [u8],
+            ) -> Result<usize, ::gatt_runtime::att::NotifyError> {
+                #store
+                conn.indication(#handle, value, configuration, pdu)
+            }
+        });
+    }
+    updates
+}
+
+/// Appends `Handle` to the last segment of an entity's type path.
+fn handle_path(type_name: &Path) -> Path {
+    let mut handle_type_name = type_name.clone();
+    let ident = &mut handle_type_name.segments.last_mut().unwrap().ident;
+    *ident = Ident::new(&(ident.to_string() + "Handle"), ident.span());
+    handle_type_name
+}
+
+/// The accessor name of an entity: its explicit name or its type name in snake case.
+fn accessor_name(name: &Option<Ident>, type_name: &Path) -> Ident {
+    name.clone()
+        .or_else(|| {
+            type_name
+                .get_ident()
+                .map(|i| Ident::new(&i.to_string().to_snek_case(), i.span()))
+        })
+        .unwrap()
+}
+
+/// Includes the files the tree and its assigned numbers were loaded from, so the crate is rebuilt
+/// when they change.
+fn file_dependency(server_parsed: &GattServerParsed) -> TokenStream {
+    let paths = server_parsed
+        .file
+        .iter()
+        .chain(&server_parsed.assigned.files);
+    quote! { #(const _: &[u8] = include_bytes!(#paths);)* }
+}
+
+/// Validates the parsed tree and lays out its attribute table, combining the errors of every
+/// service.
+fn lay_out(server_parsed: &GattServerParsed) -> Result<GattServer> {
+    let mut server = GattServer {
+        services: vec![],
+        characteristics: vec![],
+        descriptors: vec![],
+        attributes: vec![],
+        assigned: server_parsed.assigned.clone(),
+    };
+
+    // Only lay out a structurally sound tree, but still collect the errors of every service.
+    let mut errors = validate::validate(&server_parsed.struct_likes);
+    if errors.is_empty() {
+        for child in &server_parsed.struct_likes {
+            if let Err(e) = recurse_structs(&mut server, child, None) {
+                errors.push(e);
+            }
+        }
+    }
+
+    if let Some(error) = errors.into_iter().reduce(|mut error, next| {
+        error.combine(next);
+        error
+    }) {
+        return Err(error);
+    }
+    Ok(server)
+}
+
+/// Expands `gatt_server!` into a `gatt_server` module holding the attribute table and typed
+/// handles to it.
+pub fn gatt_server(input: TokenStream) -> TokenStream {
+    let server_parsed = match syn::parse2::<GattServerParsed>(input) {
+        Ok(server_parsed) => server_parsed,
+        Err(error) => return error.to_compile_error(),
+    };
+    let server = match lay_out(&server_parsed) {
+        Ok(server) => server,
+        Err(error) => return error.to_compile_error(),
+    };
+
+    let connections = match &server_parsed.connections {
+        Some(connections) => match connections.base10_parse::<usize>() {
+            Ok(0) => Err(Error::new(
+                connections.span(),
+                "A server needs at least one connection",
+            )),
+            Ok(count) if count > u16::MAX as usize => Err(Error::new(
+                connections.span(),
+                "A server can have at most 65535 connections",
+            )),
+            result => result,
+        },
+        None => Ok(1),
+    };
+    let connections = match connections {
+        Ok(connections) => connections,
+        Err(error) => return error.to_compile_error(),
+    };
+
+    let describe = export::describe(&server);
+    let attribute_count = server.attributes.len();
+    // The sizes of typed values are only known to the compiler, so offsets are const expressions.
+    let mut store_size = quote! { 0usize };
+    // Copies the initial values into the zeroed store at compile time.
+    let mut store_init = vec![];
+    // The copies of per connection values, which the attribute table refers to.
+    let mut connection_values = vec![];
+
+    let attributes = server
+        .attributes
+        .iter()
+        .map(|a| {
+            let att_type = a.att_type;
+            let handle = a.handle;
+            let permissions = a.permissions;
+            let value = match (a.kind.declaration_value(), a.kind.store_size()) {
+                (Some(bytes), _) => quote! { ::gatt_runtime::Value::Const(&[#(#bytes),*]) },
+                (None, Some(size)) => {
+                    let start = store_size.clone();
+                    let variable = matches!(a.kind, AttributeKind::Value { variable: true, .. });
+                    if let AttributeKind::Value {
+                        initial: Some((bytes, span)),
+                        ..
+                    } = &a.kind
+                    {
+                        let len = bytes.len();
+                        // Variable length initializers were checked against their maximum.
+                        let check = if variable {
+                            quote! {}
+                        } else {
+                            quote_spanned! {*span=>
+                                assert!(
+                                    #len == #size,
+                                    "The initializer does not match the size of the value type"
+                                );
+                            }
+                        };
+                        store_init.push(quote_spanned! {*span=>
+                            #check
+                            let initial: [u8; #len] = [#(#bytes),*];
+                            let mut i = 0;
+                            while i < #len {
+                                store[#start + i] = initial[i];
+                                i += 1;
+                            }
+                        });
+                    }
+                    if let AttributeKind::Value {
+                        value_type,
+                        variable: false,
+                        initial: None,
+                        ..
+                    } = &a.kind
+                    {
+                        store_init.push(quote_spanned! {value_type.span()=>
+                            assert!(
+                                <#value_type as ::gatt_runtime::GattValue>::ZEROED_IS_VALID,
+                                "The value type is invalid when zeroed, so the attribute needs an initializer"
+                            );
+                        });
+                    }
+                    if a.kind.per_connection() {
+                        store_size = quote! { #store_size + #size * #connections };
+                        let name = format_ident!("CONNECTION_VALUES_{}", handle);
+                        connection_values.push(quote! {
+                            static #name: [::gatt_runtime::Value; #connections] =
+                                DATA_STORE.values(#start, #size);
+                        });
+                        quote! { ::gatt_runtime::Value::PerConnection(&#name) }
+                    } else if variable {
+                        store_size = quote! { #store_size + #size };
+                        quote! { DATA_STORE.variable(#start, #size - 2) }
+                    } else {
+                        store_size = quote! { #store_size + #size };
+                        quote! { DATA_STORE.value(#start, #size) }
+                    }
+                }
+                (None, None) => unreachable!("Only declarations have a constant value"),
+            };
+            quote! {
+                ::gatt_runtime::Attribute {
+                    att_type: #att_type,
+                    handle: #handle,
+                    permissions: ::gatt_runtime::Permissions::from_bits(#permissions),
+                    value: #value
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let service_count = server.services.len();
+
+    let services = server
+        .services
+        .iter()
+        .map(|s| {
+            let uuid = s.uuid;
+            let attributes = sub_slice(quote! { ATTRIBUTES }, &s.attributes);
+            let characteristics = sub_slice(quote! { CHARACTERISTICS }, &s.characteristics);
+            quote! {
+                ::gatt_runtime::Service {
+                    uuid: #uuid,
+                    attributes: #attributes,
+                    characteristics: #characteristics
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let characteristic_count = server.characteristics.len();
+
+    let characteristics = server
+        .characteristics
+        .iter()
+        .map(|c| {
+            let uuid = c.uuid;
+            let properties = c.properties;
+            let attributes = sub_slice(quote! { ATTRIBUTES }, &c.attributes);
+            let descriptors = sub_slice(quote! { DESCRIPTORS }, &c.descriptors);
+            quote! {
+                ::gatt_runtime::Characteristic {
+                    uuid: #uuid,
+                    properties: #properties,
+                    attributes: #attributes,
+                    descriptors: #descriptors
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let descriptor_count = server.descriptors.len();
+
+    let descriptors = server
+        .descriptors
+        .iter()
+        .map(|d| {
+            let uuid = d.uuid;
+            let attributes = sub_slice(quote! { ATTRIBUTES }, &d.attributes);
+            quote! {
+                ::gatt_runtime::Descriptor {
+                    uuid: #uuid,
+                    attributes: #attributes,
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let (service_getters, service_types) = server
+        .services
+        .iter()
+        .enumerate()
+        .map(|(i, s)| {
+            let fn_name = accessor_name(&s.name, &s.type_name);
+            let type_name = s.type_name.clone();
+            let handle_type_name = handle_path(&s.type_name);
+            let handle = server.attributes[s.attributes.start].handle;
+            let attribute_accessors = attribute_accessors(&server.attributes[s.attributes.clone()]);
+
+            let (cfn_name, chandle_name) = server.characteristics[s.characteristics.clone()]
+                .iter()
+                .map(|c| {
+                    (
+                        accessor_name(&c.name, &c.type_name),
+                        handle_path(&c.type_name),
+                    )
+                })
+                .unzip::<_, _, Vec<_>, Vec<_>>();
+
+            (
+                quote! {
+                    pub fn #fn_name(&mut self) -> #handle_type_name {
+                        #handle_type_name {
+                            pd: core::marker::PhantomData {}
+                        }
+                    }
+                },
+                quote! {
+                    pub struct #handle_type_name<'a> {
+                        pd: core::marker::PhantomData<&'a mut GattServer>
+                    }
+
+                    impl core::ops::Deref for #handle_type_name<'_> {
+                        type Target = #type_name;
+
+                        fn deref(&self) -> &Self::Target {
+                            ::gatt_runtime::Wrapper::from_ref(&SERVICES[#i])
+                        }
+                    }
+
+                    impl #handle_type_name<'_> {
+                        /// The handle of the service declaration.
+                        pub const HANDLE: u16 = #handle;
+
+                        #(#attribute_accessors)*
+
+                        #(
+                            pub fn #cfn_name(&mut self) -> #chandle_name {
+                                #chandle_name {
+                                    pd: core::marker::PhantomData {}
+                                }
+                            }
+                        )*
+                    }
+                },
+            )
+        })
+        .unzip::<_, _, Vec<_>, Vec<_>>();
+
+    let characteristic_types = server
+        .characteristics
+        .iter()
+        .enumerate()
+        .map(|(i, s)| {
+            let type_name = s.type_name.clone();
+            let handle_type_name = handle_path(&s.type_name);
+            let handle = server.attributes[s.attributes.start].handle;
+            let attribute_accessors = attribute_accessors(&server.attributes[s.attributes.clone()]);
+            let value_updates = value_updates(&server, s);
+
+            let (cfn_name, chandle_name) = server.descriptors[s.descriptors.clone()]
+                .iter()
+                .filter_map(|c| {
+                    let type_name = c.type_name.as_ref()?;
+                    Some((accessor_name(&c.name, type_name), handle_path(type_name)))
+                })
+                .unzip::<_, _, Vec<_>, Vec<_>>();
+
+            quote! {
+                pub struct #handle_type_name<'a> {
+                    pd: core::marker::PhantomData<&'a mut GattServer>
+                }
+
+                impl core::ops::Deref for #handle_type_name<'_> {
+                    type Target = #type_name;
+
+                    fn deref(&self) -> &Self::Target {
+                        ::gatt_runtime::Wrapper::from_ref(&CHARACTERISTICS[#i])
+                    }
+                }
+
+                impl #handle_type_name<'_> {
+                    /// The handle of the characteristic declaration.
+                    pub const HANDLE: u16 = #handle;
+
+                    #(#attribute_accessors)*
+
+                    #value_updates
+
+                    #(
+                        pub fn #cfn_name(&mut self) -> #chandle_name {
+                            #chandle_name {
+                                pd: core::marker::PhantomData {}
+                            }
+                        }
+                    )*
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let descriptor_types = server
+        .descriptors
+        .iter()
+        .enumerate()
+        // Synthesized descriptors are only reachable through the attribute table.
+        .filter_map(|(i, s)| Some((i, s, s.type_name.as_ref()?)))
+        .map(|(i, s, type_name)| {
+            let handle_type_name = handle_path(type_name);
+            let handle = s.attributes.start as u16 + 1;
+
+            let attribute_accessors = attribute_accessors(&server.attributes[s.attributes.clone()]);
+
+            quote! {
+                pub struct #handle_type_name<'a> {
+                    pd: core::marker::PhantomData<&'a mut GattServer>
+                }
+
+                impl core::ops::Deref for #handle_type_name<'_> {
+                    type Target = #type_name;
+
+                    fn deref(&self) -> &Self::Target {
+                        ::gatt_runtime::Wrapper::from_ref(&DESCRIPTORS[#i])
+                    }
+                }
+
+                impl #handle_type_name<'_> {
+                    /// The handle of the first attribute of the descriptor.
+                    pub const HANDLE: u16 = #handle;
+
+                    #(#attribute_accessors)*
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // Writes from clients have to hold a valid encoding of the value type.
+    let (validated_indices, validated_types) = server
+        .attributes
+        .iter()
+        .filter_map(|a| match &a.kind {
+            AttributeKind::Value {
+                value_type,
+                variable: false,
+                ..
+            } => Some((a.handle as usize - 1, value_type)),
+            _ => None,
+        })
+        .unzip::<_, _, Vec<_>, Vec<_>>();
+
+    // Callbacks get the handle of the characteristic value they are declared for.
+    let callback_handle = |a: &Attribute, type_name: &Path| {
+        let handle_type_name = handle_path(type_name);
+        let index = a.handle as usize - 1;
+        quote! {
+            &mut #handle_type_name {
+                inner: &ATTRIBUTES[#index],
+                pd: core::marker::PhantomData {}
+            }
+        }
+    };
+    let (read_indices, read_callbacks) = server
+        .attributes
+        .iter()
+        .filter_map(|a| match &a.kind {
+            AttributeKind::Value {
+                on_read: Some(on_read),
+                type_name,
+                ..
+            } => {
+                let handle = callback_handle(a, type_name);
+                Some((a.handle as usize - 1, quote! { #on_read(#handle) }))
+            }
+            _ => None,
+        })
+        .unzip::<_, _, Vec<_>, Vec<_>>();
+    let (write_indices, write_callbacks) = server
+        .attributes
+        .iter()
+        .filter_map(|a| match &a.kind {
+            AttributeKind::Value {
+                on_write: Some(on_write),
+                type_name,
+                ..
+            } => {
+                let handle = callback_handle(a, type_name);
+                Some((a.handle as usize - 1, quote! { #on_write(#handle, value) }))
+            }
+            _ => None,
+        })
+        .unzip::<_, _, Vec<_>, Vec<_>>();
+
+    // Every attribute clients may write gets a variant carrying the written value.
+    let (event_variants, (event_indices, event_values)) = server
+        .attributes
+        .iter()
+        .filter(|a| a.permissions & flags::PERMISSION_ANY_WRITE != 0)
+        .filter_map(|a| match &a.kind {
+            AttributeKind::Value {
+                type_name,
+                value_type,
+                variable,
+                ..
+            } => {
+                let variant = &type_name.segments.last().unwrap().ident;
+                let (field, value) = if *variable {
+                    // The written bytes are copied into an array of the maximum size.
+                    (
+                        quote! { value: #value_type, len: usize },
+                        quote! {
+                            #variant {
+                                connection,
+                                value: {
+                                    let mut bytes = [0; ::core::mem::size_of::<#value_type>()];
+                                    bytes[..value.len()].copy_from_slice(value);
+                                    bytes
+                                },
+                                len: value.len(),
+                            }
+                        },
+                    )
+                } else {
+                    (
+                        quote! { value: #value_type },
+                        quote! {
+                            #variant {
+                                connection,
+                                value: <#value_type as ::gatt_runtime::GattValue>::from_bytes(value),
+                            }
+                        },
+                    )
+                };
+                Some((
+                    quote! { #variant { connection: ::gatt_runtime::att::ConnectionId, #field } },
+                    (a.handle as usize - 1, value),
+                ))
+            }
+            _ => None,
+        })
+        .unzip::<_, _, Vec<_>, (Vec<_>, Vec<_>)>();
+
+    // Attributes sharing a type share its handle type, which only has a `HANDLE` for one.
+    let value_types = server
+        .attributes
+        .iter()
+        .filter_map(|a| match &a.kind {
+            AttributeKind::Value {
+                type_name,
+                value_type,
+                variable,
+                per_connection,
+                ..
+            } => Some((a.handle, type_name, value_type, *variable, *per_connection)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let attribute_types = value_types
+        .iter()
+        .enumerate()
+        .filter(|(i, (_, type_name, ..))| {
+            !value_types[..*i].iter().any(|(_, other, ..)| other == type_name)
+        })
+        .map(|(_, &(handle, type_name, value_type, variable, per_connection))| {
+            let shared = value_types
+                .iter()
+                .filter(|(_, other, ..)| *other == type_name)
+                .count()
+                > 1;
+            let handle = if shared {
+                quote! {}
+            } else {
+                quote! {
+                    /// The handle of the attribute.
+                    pub const HANDLE: u16 = #handle;
+                }
+            };
+            let handle_type_name = handle_path(type_name);
+            // Handles borrow the server exclusively, so nothing writes the values they read, here
+            // and in `notify` and `indicate`.
+            let access = if per_connection {
+                quote! {
+                    /// The value the client on `connection` configured, `None` if the server
+                    /// keeps no values for the connection.
+                    pub fn get(&self, connection: ::gatt_runtime::att::ConnectionId) -> Option<#value_type> {
+                        let value = self.inner.value.of(connection)?;
+                        Some(::gatt_runtime::GattValue::from_bytes(unsafe { value.bytes() }))
+                    }
+
+                    /// Sets the value for the client on `connection`, ignored if the server keeps
+                    /// no values for the connection.
+                    pub fn set(&mut self, connection: ::gatt_runtime::att::ConnectionId, value: #value_type) {
+                        if let Some(stored) = self.inner.value.of(connection) {
+                            let _ = unsafe {
+                                stored.update(|bytes| ::gatt_runtime::GattValue::to_bytes(&value, bytes))
+                            };
+                        }
+                    }
+                }
+            } else if variable {
+                quote! {
+                    /// The most bytes the value can hold.
+                    pub const MAX_LEN: usize = <#value_type as ::gatt_runtime::GattValue>::SIZE;
+
+                    /// The bytes currently held.
+                    pub fn get(&self) -> &[u8] {
+                        unsafe { self.inner.value.bytes() }
+                    }
+
+                    /// The number of bytes currently held.
+                    pub fn len(&self) -> usize {
+                        self.get().len()
+                    }
+
+                    pub fn is_empty(&self) -> bool {
+                        self.len() == 0
+                    }
+
+                    /// Replaces the value, which fails with `InvalidAttributeValueLength` if it is
+                    /// longer than [`Self::MAX_LEN`].
+                    pub fn set(&mut self, value: &[u8]) -> Result<(), ::gatt_runtime::att::ErrorCode> {
+                        unsafe { self.inner.value.write(value) }
+                    }
+                }
+            } else {
+                quote! {
+                    pub fn get(&self) -> #value_type {
+                        ::gatt_runtime::GattValue::from_bytes(unsafe { self.inner.value.bytes() })
+                    }
+
+                    pub fn set(&mut self, value: #value_type) {
+                        // Values of attributes with a handle type always live in the store.
+                        let _ = unsafe {
+                            self.inner.value.update(|bytes| ::gatt_runtime::GattValue::to_bytes(&value, bytes))
+                        };
+                    }
+                }
+            };
+
+            quote! {
+                pub struct #handle_type_name<'a> {
+                    inner: &'a ::gatt_runtime::Attribute,
+                    pd: core::marker::PhantomData<&'a mut GattServer>,
+                }
+
+                impl core::ops::Deref for #handle_type_name<'_> {
+                    type Target = #type_name;
+
+                    fn deref(&self) -> &Self::Target {
+                        ::gatt_runtime::Wrapper::from_ref(self.inner)
+                    }
+                }
+
+                impl #handle_type_name<'_> {
+                    #handle
+
+                    #access
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let file_dependency = file_dependency(&server_parsed);
+
+    quote! {
+        #file_dependency
+
+        mod gatt_server {
+            use super::*;
+            static DATA_STORE: ::gatt_runtime::Store<{ #store_size }> = ::gatt_runtime::Store::new({
+                #[allow(unused_mut)]
+                let mut store = [0; #store_size];
+                #({ #store_init })*
+                store
+            });
+            static ATTRIBUTES: [::gatt_runtime::Attribute; #attribute_count] = [#(#attributes,)*];
+            static SERVICES: [::gatt_runtime::Service; #service_count] = [#(#services,)*];
+            static CHARACTERISTICS: [::gatt_runtime::Characteristic; #characteristic_count] = [#(#characteristics,)*];
+            static DESCRIPTORS: [::gatt_runtime::Descriptor; #descriptor_count] = [#(#descriptors,)*];
+            #(#connection_values)*
+
+            static mut GATT_SERVER_TAKEN: bool = false;
+
+            /// The sole capability to access the attribute values.
+            ///
+            /// Handles borrow the server, so values can only be written while nothing else reads them.
+            pub struct GattServer {
+                // Keeps other modules from building a second server next to the one `take` hands
+                // out.
+                _private: (),
+            }
+
+            impl GattServer {
+                /// Returns the server the first time it is called and `None` afterwards.
+                ///
+                /// Needs a `critical-section` implementation for the target.
+                pub fn take() -> Option<Self> {
+                    ::gatt_runtime::critical_section::with(|_| {
+                        if unsafe { GATT_SERVER_TAKEN } {
+                            None
+                        } else {
+                            Some(unsafe { GattServer::steal() })
+                        }
+                    })
+                }
+
+                /// Returns the server regardless of whether it was taken before.
+                ///
+                /// # Safety
+                ///
+                /// Any other `GattServer` must not be used while the returned one is alive.
+                pub unsafe fn steal() -> Self {
+                    GATT_SERVER_TAKEN = true;
+                    GattServer { _private: () }
+                }
+
+                /// Forgets what the client on `connection` configured, such as its subscriptions, so
+                /// the next client on the connection starts out fresh.
+                pub fn disconnect(&mut self, connection: ::gatt_runtime::att::ConnectionId) {
+                    for attribute in ATTRIBUTES.iter() {
+                        if let (::gatt_runtime::Value::PerConnection(_), Some(value)) =
+                            (attribute.value, attribute.value.of(connection))
+                        {
+                            unsafe { value.clear() };
+                        }
+                    }
+                }
+
+                #(#service_getters)*
+            }
+
+            /// A write of a client to one of the attributes it may write.
+            ///
+            /// Variable length values come with the number of bytes written, the rest of the
+            /// array is zeroed.
+            pub enum GattEvent {
+                #(#event_variants,)*
+            }
+
+            impl ::gatt_runtime::Database for GattServer {
+                type Event = GattEvent;
+
+                fn connections(&self) -> usize {
+                    #connections
+                }
+
+                fn attribute_count(&self) -> usize {
+                    ATTRIBUTES.len()
+                }
+
+                fn att_type(&self, index: usize) -> ::gatt_runtime::Uuid {
+                    ATTRIBUTES[index].att_type
+                }
+
+                fn permissions(&self, index: usize) -> ::gatt_runtime::Permissions {
+                    ATTRIBUTES[index].permissions
+                }
+
+                fn prepare_read(&mut self, index: usize) -> Result<(), ::gatt_runtime::att::ErrorCode> {
+                    match index {
+                        #(#read_indices => #read_callbacks,)*
+                        _ => Ok(()),
+                    }
+                }
+
+                fn value(&self, connection: ::gatt_runtime::att::ConnectionId, index: usize) -> &[u8] {
+                    // Writes need exclusive access to the server. Connections the server keeps no
+                    // values for see none.
+                    ATTRIBUTES[index].value.of(connection).map_or(&[], |value| unsafe { value.bytes() })
+                }
+
+                fn write(
+                    &mut self,
+                    connection: ::gatt_runtime::att::ConnectionId,
+                    index: usize,
+                    value: &[u8],
+                ) -> Result<Option<GattEvent>, ::gatt_runtime::att::ErrorCode> {
+                    let stored = ATTRIBUTES[index]
+                        .value
+                        .of(connection)
+                        .ok_or(::gatt_runtime::att::ErrorCode::UnlikelyError)?;
+                    stored.check(value)?;
+                    let valid = match index {
+                        #(
+                            #validated_indices => <#validated_types as ::gatt_runtime::GattValue>::is_valid(value),
+                        )*
+                        _ => true,
+                    };
+                    if !valid {
+                        return Err(::gatt_runtime::att::ErrorCode::ValueNotAllowed);
+                    }
+                    match index {
+                        #(#write_indices => #write_callbacks?,)*
+                        _ => (),
+                    }
+                    unsafe { stored.write(value) }?;
+                    Ok(match index {
+                        #(#event_indices => Some(GattEvent::#event_values),)*
+                        _ => None,
+                    })
+                }
+
+                fn disconnect(&mut self, connection: ::gatt_runtime::att::ConnectionId) {
+                    GattServer::disconnect(self, connection)
+                }
+            }
+
+            #describe
+
+            #(#service_types)*
+
+            #(#characteristic_types)*
+
+            #(#descriptor_types)*
+
+            #(#attribute_types)*
+        }
+    }
+}
+
+/// Expands `gatt_client!` into a `gatt_client` module with a typed `GattClient`.
+pub fn gatt_client(input: TokenStream) -> TokenStream {
+    let server_parsed = match syn::parse2::<GattServerParsed>(input) {
+        Ok(server_parsed) => server_parsed,
+        Err(error) => return error.to_compile_error(),
+    };
+    match lay_out(&server_parsed) {
+        Ok(server) => {
+            let file_dependency = file_dependency(&server_parsed);
+            let client = client::gatt_client(&server);
+            quote!(#file_dependency #client)
+        }
+        Err(error) => error.to_compile_error(),
+    }
+}
+
+/// Expands `#[derive(GattValue)]`.
+pub fn derive_gatt_value(input: TokenStream) -> TokenStream {
+    match syn::parse2::<DeriveInput>(input) {
+        Ok(input) => derive::gatt_value(input).unwrap_or_else(|e| e.to_compile_error()),
+        Err(error) => error.to_compile_error(),
+    }
+}
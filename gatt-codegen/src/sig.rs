@@ -0,0 +1,475 @@
+use std::{env, fs, path::Path as FilePath};
+
+use proc_macro2::Span;
+use quote::format_ident;
+use syn::{parse_quote, Error, Ident, LitStr, Path, Result, Type};
+
+use crate::{
+    flags::{
+        self, Properties, PROPERTY_INDICATE as INDICATE, PROPERTY_NOTIFY as NOTIFY,
+        PROPERTY_READ as READ, PROPERTY_WRITE as WRITE,
+        PROPERTY_WRITE_WITHOUT_RESPONSE as WRITE_WITHOUT_RESPONSE,
+    },
+    Kind, StructLike, Uuid,
+};
+
+/// How the value of a characteristic is encoded, per the GATT Specification Supplement.
+#[derive(Debug, Clone, Copy)]
+pub enum Format {
+    /// An integer, named by the Rust type it is encoded as.
+    Int(&'static str),
+    /// A structure of a fixed number of bytes.
+    Bytes(usize),
+    /// A string or a structure with optional fields.
+    Variable,
+}
+
+impl Format {
+    /// The number of bytes a value of the format has, `None` if it varies.
+    fn size(self) -> Option<usize> {
+        match self {
+            Format::Int(name) => int_size(name),
+            Format::Bytes(size) => Some(size),
+            Format::Variable => None,
+        }
+    }
+}
+
+/// A characteristic the Bluetooth SIG assigned a UUID and specified the value of.
+#[derive(Debug)]
+pub struct Characteristic {
+    /// The name in upper camel case, which the type of a `characteristic` is matched against.
+    pub name: &'static str,
+    pub uuid: u16,
+    pub format: Format,
+    /// The properties the characteristic must have.
+    pub mandatory: u8,
+    /// The properties the characteristic may have in addition.
+    pub optional: u8,
+    /// The descriptors the characteristic must have, besides the client characteristic
+    /// configuration which is generated for it.
+    pub descriptors: &'static [u16],
+}
+
+// The tables are a subset of the Assigned Numbers and the GATT Specification Supplement, typed
+// in by hand: the services of the common profiles with their characteristics and descriptors.
+// The UUIDs of other names are looked up in the assigned numbers an `assigned_numbers = "..."`
+// clause points to, `missing` reports names found in neither.
+
+const SERVICES: &[(&str, u16)] = &[
+    ("GenericAccess", 0x1800),
+    ("GenericAttribute", 0x1801),
+    ("ImmediateAlert", 0x1802),
+    ("LinkLoss", 0x1803),
+    ("TxPower", 0x1804),
+    ("CurrentTime", 0x1805),
+    ("HealthThermometer", 0x1809),
+    ("DeviceInformation", 0x180A),
+    ("HeartRate", 0x180D),
+    ("Battery", 0x180F),
+    ("HumanInterfaceDevice", 0x1812),
+    ("EnvironmentalSensing", 0x181A),
+];
+
+const DESCRIPTORS: &[(&str, u16)] = &[
+    ("CharacteristicExtendedProperties", 0x2900),
+    ("CharacteristicUserDescription", 0x2901),
+    ("ClientCharacteristicConfiguration", 0x2902),
+    ("ServerCharacteristicConfiguration", 0x2903),
+    ("CharacteristicPresentationFormat", 0x2904),
+    ("CharacteristicAggregateFormat", 0x2905),
+    ("ValidRange", 0x2906),
+    ("ExternalReportReference", 0x2907),
+    ("ReportReference", 0x2908),
+    ("EnvironmentalSensingConfiguration", 0x290B),
+    ("EnvironmentalSensingMeasurement", 0x290C),
+    ("EnvironmentalSensingTriggerSetting", 0x290D),
+];
+
+macro_rules! characteristics {
+    ($($name:ident $uuid:literal $format:expr, $mandatory:expr, $optional:expr $(, [$($descriptor:literal),*])?;)*) => {
+        &[$(Characteristic {
+            name: stringify!($name),
+            uuid: $uuid,
+            format: $format,
+            mandatory: $mandatory,
+            optional: $optional,
+            descriptors: &[$($($descriptor),*)?],
+        }),*]
+    };
+}
+
+use Format::{Bytes, Int, Variable};
+
+const CHARACTERISTICS: &[Characteristic] = characteristics! {
+    DeviceName 0x2A00 Variable, READ, WRITE;
+    Appearance 0x2A01 Int("u16"), READ, WRITE;
+    PeripheralPreferredConnectionParameters 0x2A04 Bytes(8), READ, 0;
+    ServiceChanged 0x2A05 Bytes(4), INDICATE, 0;
+    TxPowerLevel 0x2A07 Int("i8"), READ, NOTIFY;
+    DateTime 0x2A08 Bytes(7), READ, WRITE | NOTIFY;
+    BatteryLevel 0x2A19 Int("u8"), READ, NOTIFY;
+    TemperatureMeasurement 0x2A1C Variable, INDICATE, 0;
+    IntermediateTemperature 0x2A1E Variable, NOTIFY, 0;
+    SystemId 0x2A23 Bytes(8), READ, 0;
+    ModelNumberString 0x2A24 Variable, READ, 0;
+    SerialNumberString 0x2A25 Variable, READ, 0;
+    FirmwareRevisionString 0x2A26 Variable, READ, 0;
+    HardwareRevisionString 0x2A27 Variable, READ, 0;
+    SoftwareRevisionString 0x2A28 Variable, READ, 0;
+    ManufacturerNameString 0x2A29 Variable, READ, 0;
+    CurrentTime 0x2A2B Bytes(10), READ | NOTIFY, WRITE;
+    HeartRateMeasurement 0x2A37 Variable, NOTIFY, 0;
+    BodySensorLocation 0x2A38 Int("u8"), READ, 0;
+    HeartRateControlPoint 0x2A39 Int("u8"), WRITE, 0;
+    Report 0x2A4D Variable, READ, WRITE | WRITE_WITHOUT_RESPONSE | NOTIFY, [0x2908];
+    ProtocolMode 0x2A4E Int("u8"), READ | WRITE_WITHOUT_RESPONSE, 0;
+    PnpId 0x2A50 Bytes(7), READ, 0;
+    Pressure 0x2A6D Int("u32"), READ, NOTIFY;
+    Temperature 0x2A6E Int("i16"), READ, NOTIFY;
+    Humidity 0x2A6F Int("u16"), READ, NOTIFY;
+    CentralAddressResolution 0x2AA6 Int("u8"), READ, 0;
+};
+
+/// The names and UUIDs of the services, characteristics and descriptors in a checkout of the
+/// Bluetooth SIG's assigned numbers.
+#[derive(Debug, Clone, Default)]
+pub struct AssignedNumbers {
+    services: Vec<(String, u16)>,
+    characteristics: Vec<(String, u16)>,
+    descriptors: Vec<(String, u16)>,
+    /// The paths of the files read, which the generated code includes so it is rebuilt when they
+    /// change.
+    pub files: Vec<String>,
+}
+
+impl AssignedNumbers {
+    /// Reads the `uuids` directory of the `assigned_numbers` directory at `path`, relative to the
+    /// directory of the crate's `Cargo.toml`.
+    pub fn load(path: &LitStr) -> Result<Self> {
+        let directory = FilePath::new(&env::var_os("CARGO_MANIFEST_DIR").unwrap_or_default())
+            .join(path.value())
+            .join("uuids");
+        let mut assigned = AssignedNumbers::default();
+        for (name, table) in [
+            ("service_uuids.yaml", &mut assigned.services),
+            ("characteristic_uuids.yaml", &mut assigned.characteristics),
+            ("descriptors.yaml", &mut assigned.descriptors),
+        ] {
+            let file = directory.join(name);
+            let contents = fs::read_to_string(&file).map_err(|error| {
+                Error::new(
+                    path.span(),
+                    format!("Failed to read `{}`: {}", file.display(), error),
+                )
+            })?;
+            *table = parse_uuids(&contents).map_err(|(line, message)| {
+                Error::new(
+                    path.span(),
+                    format!("{}:{}: {}", file.display(), line, message),
+                )
+            })?;
+            assigned.files.push(file.display().to_string());
+        }
+        Ok(assigned)
+    }
+}
+
+/// Reads the entries of an assigned numbers file, which lists them as
+///
+/// ```text
+/// uuids:
+///  - uuid: 0x180F
+///    name: Battery
+///    id: org.bluetooth.service.battery
+/// ```
+///
+/// Returns the line and message of the first malformed entry.
+fn parse_uuids(contents: &str) -> std::result::Result<Vec<(String, u16)>, (usize, String)> {
+    let mut entries = vec![];
+    // The line an entry starts at, its name and UUID.
+    let mut entry: Option<(usize, Option<String>, Option<u16>)> = None;
+    let mut finish = |entry: Option<(usize, Option<String>, Option<u16>)>| match entry {
+        Some((_, Some(name), Some(uuid))) => {
+            entries.push((camel_case(&name), uuid));
+            Ok(())
+        }
+        Some((line, ..)) => Err((line, "Entries need a `uuid` and a `name`".to_string())),
+        None => Ok(()),
+    };
+    for (i, line) in contents.lines().enumerate() {
+        let mut line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line == "uuids:" {
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("- ") {
+            finish(entry.take())?;
+            entry = Some((i + 1, None, None));
+            line = rest.trim_start();
+        }
+        let (key, value) = match (line.split_once(':'), &mut entry) {
+            (Some((key, value)), Some(_)) => (key.trim(), value.trim()),
+            _ => {
+                return Err((
+                    i + 1,
+                    format!("Expected an entry of `uuids`, found `{}`", line),
+                ))
+            }
+        };
+        let (_, name, uuid) = entry.as_mut().unwrap();
+        match key {
+            "uuid" => {
+                let parsed = value
+                    .strip_prefix("0x")
+                    .and_then(|hex| u16::from_str_radix(hex, 16).ok());
+                *uuid = Some(parsed.ok_or_else(|| {
+                    (
+                        i + 1,
+                        format!("Expected a 16-bit UUID like `0x180F`, found `{}`", value),
+                    )
+                })?);
+            }
+            "name" => *name = Some(value.trim_matches(|c| c == '\'' || c == '"').to_string()),
+            _ => (),
+        }
+    }
+    finish(entry)?;
+    Ok(entries)
+}
+
+/// The type name of an assigned name, `BatteryLevel` for `Battery Level` and `PnpId` for
+/// `PnP ID`.
+fn camel_case(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .flat_map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase())
+                .into_iter()
+                .chain(chars.map(|c| c.to_ascii_lowercase()))
+        })
+        .collect()
+}
+
+/// The UUID the SIG assigned to `ident` in `table`.
+fn lookup(table: &[(String, u16)], ident: &Ident) -> Option<u16> {
+    table
+        .iter()
+        .find(|(name, _)| ident == name)
+        .map(|(_, uuid)| *uuid)
+}
+
+/// The width in bytes of an integer type name.
+fn int_size(name: &str) -> Option<usize> {
+    Some(match name {
+        "u8" | "i8" => 1,
+        "u16" | "i16" => 2,
+        "u32" | "i32" => 4,
+        "u64" | "i64" => 8,
+        "u128" | "i128" => 16,
+        _ => return None,
+    })
+}
+
+fn type_ident(input: &StructLike) -> &Ident {
+    &input.type_name.segments.last().unwrap().ident
+}
+
+/// The UUID of a service, characteristic or descriptor: its `uuid = ...` clause or the UUID the
+/// SIG assigned to its type name.
+pub fn uuid(assigned: &AssignedNumbers, input: &StructLike) -> Option<Uuid> {
+    let ident = type_ident(input);
+    let (loaded, table) = match input.kind {
+        _ if input.uuid.is_some() => return input.uuid,
+        Kind::Service => (&assigned.services, SERVICES),
+        Kind::Descriptor => (&assigned.descriptors, DESCRIPTORS),
+        Kind::Characteristic => {
+            return lookup(&assigned.characteristics, ident)
+                .or_else(|| characteristic(assigned, input).map(|c| c.uuid))
+                .map(Uuid::Uuid16)
+        }
+        Kind::Attribute => return None,
+    };
+    lookup(loaded, ident)
+        .or_else(|| {
+            table
+                .iter()
+                .find(|(name, _)| ident == name)
+                .map(|(_, uuid)| *uuid)
+        })
+        .map(Uuid::Uuid16)
+}
+
+/// The error for a service, characteristic or descriptor without a `uuid = ...` clause whose
+/// type is not named after one in the tables, which only hold a subset of the assigned numbers,
+/// or in the assigned numbers loaded.
+pub fn missing(assigned: &AssignedNumbers, input: &StructLike) -> Error {
+    let hint = if assigned.files.is_empty() {
+        ", add an `assigned_numbers = \"...\"` clause to look it up in all of them"
+    } else {
+        ""
+    };
+    Error::new_spanned(
+        &input.type_name,
+        format!(
+            "Missing `uuid = ...` clause for `{}`, which is not among the assigned numbers the \
+             macro knows{}",
+            type_ident(input),
+            hint
+        ),
+    )
+}
+
+/// The specification of a characteristic named after one the SIG assigned. Characteristics
+/// with a `uuid = ...` clause are the user's own.
+pub fn characteristic(
+    assigned: &AssignedNumbers,
+    input: &StructLike,
+) -> Option<&'static Characteristic> {
+    if input.uuid.is_some() {
+        return None;
+    }
+    let ident = type_ident(input);
+    // The loaded assigned numbers may name a characteristic differently than the tables.
+    match lookup(&assigned.characteristics, ident) {
+        Some(uuid) => CHARACTERISTICS.iter().find(|c| c.uuid == uuid),
+        None => CHARACTERISTICS.iter().find(|c| ident == c.name),
+    }
+}
+
+impl Characteristic {
+    /// Checks the `properties(...)` of the characteristic against the specification.
+    pub fn check_properties(&self, input: &StructLike, properties: &Properties) -> Result<()> {
+        for ident in &properties.0.idents {
+            let bits = flags::property_bits(ident);
+            if bits & (self.mandatory | self.optional) != bits {
+                return Err(Error::new(
+                    ident.span(),
+                    format!(
+                        "`{}` cannot have the {} property per the Bluetooth specification",
+                        self.name, ident
+                    ),
+                ));
+            }
+        }
+        let missing = flags::property_names(self.mandatory & !properties.0.bits);
+        if !missing.is_empty() {
+            return Err(Error::new_spanned(
+                &input.type_name,
+                format!(
+                    "`{}` needs the {} property per the Bluetooth specification",
+                    self.name,
+                    missing.join(" and ")
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Checks that the characteristic has the descriptors the specification requires.
+    pub fn check_descriptors(
+        &self,
+        assigned: &AssignedNumbers,
+        input: &StructLike,
+        descriptors: &[&StructLike],
+    ) -> Result<()> {
+        for required in self.descriptors {
+            if !descriptors
+                .iter()
+                .any(|d| uuid(assigned, d) == Some(Uuid::Uuid16(*required)))
+            {
+                let name = DESCRIPTORS
+                    .iter()
+                    .find(|(_, uuid)| uuid == required)
+                    .map_or_else(
+                        || format!("{:#06X}", required),
+                        |(name, _)| name.to_string(),
+                    );
+                return Err(Error::new_spanned(
+                    &input.type_name,
+                    format!(
+                        "`{}` needs a `{}` descriptor per the Bluetooth specification",
+                        self.name, name
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// The value attribute generated for a characteristic declared without one, `None` if the
+    /// size of its value varies and has to be declared.
+    ///
+    /// It is named after the characteristic, `BatteryLevelValue` for a `BatteryLevel`.
+    pub fn value_attribute(&self, input: &StructLike) -> Option<StructLike> {
+        self.format.size()?;
+        let ident = type_ident(input);
+        let name = format_ident!("{}Value", ident, span = ident.span());
+        Some(StructLike {
+            kind: Kind::Attribute,
+            span: input.span,
+            name: None,
+            type_name: Path::from(name),
+            uuid: None,
+            properties: None,
+            permissions: None,
+            on_read: None,
+            on_write: None,
+            children: vec![],
+            size: None,
+            variable: false,
+            initial: None,
+            origin: None,
+        })
+    }
+
+    /// Fills in the value type or size of the value attribute `input` from the format of the
+    /// characteristic, or checks the declared ones against it.
+    ///
+    /// Value types which are not integers, such as structs deriving `GattValue`, are taken as
+    /// they are.
+    pub fn value_format(
+        &self,
+        input: &StructLike,
+        value_type: Option<Type>,
+    ) -> Result<(Option<Type>, Option<usize>)> {
+        if let Some(value_type) = value_type {
+            let name = match &value_type {
+                Type::Path(path) => path.path.get_ident().map(|i| i.to_string()),
+                _ => None,
+            };
+            if let (Format::Int(int), Some(name)) = (self.format, name) {
+                if int_size(&name).is_some() && name != int {
+                    return Err(Error::new_spanned(
+                        &value_type,
+                        format!(
+                            "`{}` is encoded as `{}` per the Bluetooth specification, not `{}`",
+                            self.name, int, name
+                        ),
+                    ));
+                }
+            }
+            return Ok((Some(value_type), None));
+        }
+        match (&input.size, self.format) {
+            (None, Format::Int(int)) => {
+                let int = Ident::new(int, Span::call_site());
+                Ok((Some(parse_quote!(#int)), None))
+            }
+            (None, format) => Ok((None, format.size())),
+            (Some(size), format) => match format.size() {
+                Some(expected) if input.variable || size.base10_parse::<usize>()? != expected => {
+                    Err(Error::new(
+                        size.span(),
+                        format!(
+                            "`{}` has a value of {} bytes per the Bluetooth specification",
+                            self.name, expected
+                        ),
+                    ))
+                }
+                _ => Ok((None, None)),
+            },
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use std::fmt;
+
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{
+    parse::{Parse, ParseStream, Result},
+    Error, Lit,
+};
+
+/// The Bluetooth base UUID `00000000-0000-1000-8000-00805F9B34FB` in little endian byte order.
+const BASE_UUID: [u8; 16] = [
+    0xFB, 0x34, 0x9B, 0x5F, 0x80, 0x00, 0x00, 0x80, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+/// A UUID as written in a `uuid = ...` clause.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Uuid {
+    /// A 16-bit Bluetooth SIG assigned UUID, written as an integer such as `0x180F`.
+    Uuid16(u16),
+    /// A 128-bit vendor UUID, written as a string such as
+    /// `"6e400001-b5a3-f393-e0a9-e50e24dcca9e"`.
+    /// The bytes are stored little endian, the order in which they go over the air.
+    Uuid128([u8; 16]),
+}
+
+impl Uuid {
+    /// The little endian representation used in attribute values and PDUs.
+    pub fn to_bytes(self) -> Vec<u8> {
+        match self {
+            Uuid::Uuid16(uuid) => uuid.to_le_bytes().to_vec(),
+            Uuid::Uuid128(uuid) => uuid.to_vec(),
+        }
+    }
+}
+
+/// Formats UUIDs in the textual `8-4-4-4-12` form, 16-bit UUIDs as their alias of the Bluetooth
+/// base UUID.
+impl fmt::Display for Uuid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let bytes = match self {
+            Uuid::Uuid16(uuid) => {
+                let mut bytes = BASE_UUID;
+                bytes[12..14].copy_from_slice(&uuid.to_le_bytes());
+                bytes
+            }
+            Uuid::Uuid128(uuid) => *uuid,
+        };
+        for (i, byte) in bytes.iter().rev().enumerate() {
+            if [4, 6, 8, 10].contains(&i) {
+                f.write_str("-")?;
+            }
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses the textual `8-4-4-4-12` form of a 128-bit UUID into little endian bytes.
+fn parse_uuid128(text: &str) -> Option<[u8; 16]> {
+    let groups = text.split('-').map(str::len).collect::<Vec<_>>();
+    if groups != [8, 4, 4, 4, 12] {
+        return None;
+    }
+    let digits = text.replace('-', "");
+    let mut bytes = [0; 16];
+    for (i, byte) in bytes.iter_mut().rev().enumerate() {
+        *byte = u8::from_str_radix(digits.get(2 * i..2 * i + 2)?, 16).ok()?;
+    }
+    Some(bytes)
+}
+
+impl Parse for Uuid {
+    fn parse(input: ParseStream) -> Result<Self> {
+        match input.parse::<Lit>()? {
+            Lit::Int(lit) => lit.base10_parse::<u16>().map(Uuid::Uuid16).map_err(|_| {
+                Error::new(
+                    lit.span(),
+                    "16-bit UUIDs must be in the range 0x0000..=0xFFFF, use the string form for 128-bit UUIDs",
+                )
+            }),
+            Lit::Str(lit) => parse_uuid128(&lit.value())
+                .map(Uuid::Uuid128)
+                .ok_or_else(|| {
+                    Error::new(
+                        lit.span(),
+                        "128-bit UUIDs must have the form \"xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx\"",
+                    )
+                }),
+            lit => Err(Error::new(
+                lit.span(),
+                "Expected a 16-bit UUID like `0x180F` or a 128-bit UUID like \"6e400001-b5a3-f393-e0a9-e50e24dcca9e\"",
+            )),
+        }
+    }
+}
+
+impl ToTokens for Uuid {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.extend(match self {
+            Uuid::Uuid16(uuid) => quote! { ::gatt_runtime::Uuid::Uuid16(#uuid) },
+            Uuid::Uuid128(uuid) => quote! { ::gatt_runtime::Uuid::Uuid128([#(#uuid),*]) },
+        });
+    }
+}
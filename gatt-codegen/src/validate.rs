@@ -0,0 +1,89 @@
+use syn::Error;
+
+use crate::{Kind, StructLike};
+
+impl Kind {
+    fn name(&self) -> &'static str {
+        match self {
+            Kind::Service => "service",
+            Kind::Characteristic => "characteristic",
+            Kind::Descriptor => "descriptor",
+            Kind::Attribute => "attribute",
+        }
+    }
+
+    /// The kinds which may be nested directly inside this one.
+    fn children(&self) -> &'static [Kind] {
+        match self {
+            Kind::Service => &[Kind::Characteristic, Kind::Attribute],
+            Kind::Characteristic => &[Kind::Descriptor, Kind::Attribute],
+            Kind::Descriptor => &[Kind::Attribute],
+            Kind::Attribute => &[],
+        }
+    }
+}
+
+/// Checks the structure of the parsed tree before any attribute is laid out.
+///
+/// Returns every mistake instead of stopping at the first one, so they all show up in one compile.
+pub fn validate(struct_likes: &[StructLike]) -> Vec<Error> {
+    let mut errors = vec![];
+    for struct_like in struct_likes {
+        if !matches!(struct_like.kind, Kind::Service) {
+            errors.push(struct_like.locate(Error::new(
+                struct_like.span,
+                format!(
+                    "Only services can appear at the top level, found a {}",
+                    struct_like.kind.name()
+                ),
+            )));
+        }
+        validate_struct_like(struct_like, &mut errors);
+    }
+    errors
+}
+
+fn validate_struct_like(input: &StructLike, errors: &mut Vec<Error>) {
+    // Handle types and accessors are named after the type, which needs to be a plain name.
+    if input.type_name.leading_colon.is_some() || input.type_name.segments.len() != 1 {
+        errors.push(input.locate(Error::new_spanned(
+            &input.type_name,
+            format!(
+                "Types in the tree are named without a path, bring `{}` into scope with `use`",
+                input.type_name.segments.last().unwrap().ident
+            ),
+        )));
+    }
+    let allowed = input.kind.children();
+    for child in &input.children {
+        if allowed.is_empty() {
+            errors.push(child.locate(Error::new(
+                child.span,
+                "Attributes cannot have children, only a size such as `{ 4 }` or `{ max 32 }`",
+            )));
+        } else if !allowed.contains(&child.kind) {
+            let allowed = allowed
+                .iter()
+                .map(|kind| format!("{}s", kind.name()))
+                .collect::<Vec<_>>();
+            errors.push(child.locate(Error::new(
+                child.span,
+                format!(
+                    "A {} cannot contain a {}, only {}",
+                    input.kind.name(),
+                    child.kind.name(),
+                    allowed.join(" and ")
+                ),
+            )));
+        }
+        validate_struct_like(child, errors);
+    }
+    if let Some(size) = &input.size {
+        if let Err(e) = size.base10_parse::<usize>() {
+            errors.push(input.locate(Error::new(
+                size.span(),
+                format!("Invalid attribute size: {}", e),
+            )));
+        }
+    }
+}
@@ -0,0 +1,721 @@
+//! The server side of the Attribute Protocol (ATT).
+
+use crate::{transport::AttTransport, Database, Permissions, Uuid};
+
+/// The MTU every connection starts out with.
+pub const DEFAULT_MTU: u16 = 23;
+
+/// Attribute type of a primary service declaration.
+const PRIMARY_SERVICE: Uuid = Uuid::Uuid16(0x2800);
+/// Attribute type of a secondary service declaration.
+const SECONDARY_SERVICE: Uuid = Uuid::Uuid16(0x2801);
+
+pub mod opcode {
+    pub const ERROR_RESPONSE: u8 = 0x01;
+    pub const EXCHANGE_MTU_REQUEST: u8 = 0x02;
+    pub const EXCHANGE_MTU_RESPONSE: u8 = 0x03;
+    pub const FIND_INFORMATION_REQUEST: u8 = 0x04;
+    pub const FIND_INFORMATION_RESPONSE: u8 = 0x05;
+    pub const FIND_BY_TYPE_VALUE_REQUEST: u8 = 0x06;
+    pub const FIND_BY_TYPE_VALUE_RESPONSE: u8 = 0x07;
+    pub const READ_BY_TYPE_REQUEST: u8 = 0x08;
+    pub const READ_BY_TYPE_RESPONSE: u8 = 0x09;
+    pub const READ_REQUEST: u8 = 0x0A;
+    pub const READ_RESPONSE: u8 = 0x0B;
+    pub const READ_BLOB_REQUEST: u8 = 0x0C;
+    pub const READ_BLOB_RESPONSE: u8 = 0x0D;
+    pub const READ_MULTIPLE_REQUEST: u8 = 0x0E;
+    pub const READ_MULTIPLE_RESPONSE: u8 = 0x0F;
+    pub const READ_BY_GROUP_TYPE_REQUEST: u8 = 0x10;
+    pub const READ_BY_GROUP_TYPE_RESPONSE: u8 = 0x11;
+    pub const WRITE_REQUEST: u8 = 0x12;
+    pub const WRITE_RESPONSE: u8 = 0x13;
+    pub const HANDLE_VALUE_NOTIFICATION: u8 = 0x1B;
+    pub const HANDLE_VALUE_INDICATION: u8 = 0x1D;
+    pub const HANDLE_VALUE_CONFIRMATION: u8 = 0x1E;
+    pub const WRITE_COMMAND: u8 = 0x52;
+
+    /// Set in the opcode of every command, which never gets a response.
+    pub const COMMAND_FLAG: u8 = 0x40;
+}
+
+/// The error codes of an Error Response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    InvalidHandle,
+    ReadNotPermitted,
+    WriteNotPermitted,
+    InvalidPdu,
+    InsufficientAuthentication,
+    RequestNotSupported,
+    InvalidOffset,
+    InsufficientAuthorization,
+    PrepareQueueFull,
+    AttributeNotFound,
+    AttributeNotLong,
+    InsufficientEncryptionKeySize,
+    InvalidAttributeValueLength,
+    UnlikelyError,
+    InsufficientEncryption,
+    UnsupportedGroupType,
+    InsufficientResources,
+    ValueNotAllowed,
+    /// An application defined error in the range `0x80..=0x9F`.
+    Application(u8),
+}
+
+impl ErrorCode {
+    pub fn code(self) -> u8 {
+        match self {
+            ErrorCode::InvalidHandle => 0x01,
+            ErrorCode::ReadNotPermitted => 0x02,
+            ErrorCode::WriteNotPermitted => 0x03,
+            ErrorCode::InvalidPdu => 0x04,
+            ErrorCode::InsufficientAuthentication => 0x05,
+            ErrorCode::RequestNotSupported => 0x06,
+            ErrorCode::InvalidOffset => 0x07,
+            ErrorCode::InsufficientAuthorization => 0x08,
+            ErrorCode::PrepareQueueFull => 0x09,
+            ErrorCode::AttributeNotFound => 0x0A,
+            ErrorCode::AttributeNotLong => 0x0B,
+            ErrorCode::InsufficientEncryptionKeySize => 0x0C,
+            ErrorCode::InvalidAttributeValueLength => 0x0D,
+            ErrorCode::UnlikelyError => 0x0E,
+            ErrorCode::InsufficientEncryption => 0x0F,
+            ErrorCode::UnsupportedGroupType => 0x10,
+            ErrorCode::InsufficientResources => 0x11,
+            ErrorCode::ValueNotAllowed => 0x13,
+            ErrorCode::Application(code) => code,
+        }
+    }
+}
+
+/// The security of the link a request arrived on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SecurityLevel {
+    Unencrypted,
+    /// Encrypted with an unauthenticated key.
+    Encrypted,
+    /// Encrypted with a key from a pairing with MITM protection.
+    Authenticated,
+}
+
+/// Identifies the connection a PDU arrived on.
+///
+/// The id indexes the per connection values of a database, so a server generated for three
+/// connections serves the ids 0 to 2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ConnectionId(pub u16);
+
+/// What processing a PDU resulted in.
+#[derive(Debug, PartialEq)]
+pub struct Processed<E> {
+    /// The length of the response PDU, `None` if there is nothing to send.
+    pub response: Option<usize>,
+    /// The change a client made to the database.
+    pub event: Option<E>,
+    /// The handle of the indication the client confirmed.
+    pub confirmed: Option<u16>,
+}
+
+impl<E> Processed<E> {
+    fn nothing() -> Self {
+        Processed {
+            response: None,
+            event: None,
+            confirmed: None,
+        }
+    }
+}
+
+/// The bit of a client characteristic configuration which enables notifications.
+pub const NOTIFICATIONS_ENABLED: u16 = 0x0001;
+/// The bit of a client characteristic configuration which enables indications.
+pub const INDICATIONS_ENABLED: u16 = 0x0002;
+
+/// Why a notification or indication was not sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotifyError {
+    /// The client did not enable it in the client characteristic configuration.
+    NotSubscribed,
+    /// The client has not confirmed the previous indication yet.
+    IndicationPending,
+    /// The value is longer than the attribute can hold.
+    ValueTooLong,
+}
+
+/// An error to be sent in an Error Response, together with the handle it concerns.
+type Failure = (u16, ErrorCode);
+
+/// Builds a response PDU.
+struct Writer<'a> {
+    buffer: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> Writer<'a> {
+    fn new(buffer: &'a mut [u8]) -> Self {
+        Writer { buffer, len: 0 }
+    }
+
+    fn remaining(&self) -> usize {
+        self.buffer.len() - self.len
+    }
+
+    fn u8(&mut self, value: u8) {
+        self.buffer[self.len] = value;
+        self.len += 1;
+    }
+
+    fn u16(&mut self, value: u16) {
+        self.slice(&value.to_le_bytes());
+    }
+
+    fn uuid(&mut self, uuid: Uuid) {
+        uuid.write_to(&mut self.buffer[self.len..]);
+        self.len += uuid.encoded_len();
+    }
+
+    fn slice(&mut self, value: &[u8]) {
+        self.buffer[self.len..self.len + value.len()].copy_from_slice(value);
+        self.len += value.len();
+    }
+
+    /// Appends as much of `value` as fits.
+    fn truncated(&mut self, value: &[u8]) {
+        let len = value.len().min(self.remaining());
+        self.slice(&value[..len]);
+    }
+}
+
+fn u16_at(params: &[u8], offset: usize) -> Result<u16, Failure> {
+    params
+        .get(offset..offset + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        .ok_or((0, ErrorCode::InvalidPdu))
+}
+
+/// Parses the starting and ending handle which open most discovery requests.
+fn handle_range(params: &[u8]) -> Result<(u16, u16), Failure> {
+    let start = u16_at(params, 0)?;
+    let end = u16_at(params, 2)?;
+    if start == 0 || start > end {
+        return Err((start, ErrorCode::InvalidHandle));
+    }
+    Ok((start, end))
+}
+
+/// The indices of all attributes with a handle inside `start..=end`.
+fn indices(db: &impl Database, start: u16, end: u16) -> core::ops::Range<usize> {
+    let count = db.attribute_count();
+    (start as usize - 1).min(count)..(end as usize).min(count)
+}
+
+/// Whether a client characteristic configuration has `bit` set.
+fn enabled(configuration: &[u8], bit: u16) -> bool {
+    let configuration = match configuration {
+        [low, high, ..] => u16::from_le_bytes([*low, *high]),
+        [low] => *low as u16,
+        [] => 0,
+    };
+    configuration & bit != 0
+}
+
+fn is_service(uuid: Uuid) -> bool {
+    uuid == PRIMARY_SERVICE || uuid == SECONDARY_SERVICE
+}
+
+/// The handle of the last attribute in the group that starts at `index`.
+fn group_end(db: &impl Database, index: usize) -> u16 {
+    let count = db.attribute_count();
+    (index + 1..count)
+        .find(|&i| is_service(db.att_type(i)))
+        .unwrap_or(count) as u16
+}
+
+/// The state of the ATT server for one connection.
+pub struct AttServer {
+    connection: ConnectionId,
+    rx_mtu: u16,
+    mtu: u16,
+    security: SecurityLevel,
+    authorized: bool,
+    /// The handle of the indication awaiting its confirmation.
+    indication: Option<u16>,
+}
+
+impl AttServer {
+    /// Creates the server state for a new connection.
+    ///
+    /// `rx_mtu` is the largest PDU this server can receive and is announced in Exchange MTU.
+    pub const fn new(connection: ConnectionId, rx_mtu: u16) -> Self {
+        AttServer {
+            connection,
+            rx_mtu: if rx_mtu < DEFAULT_MTU {
+                DEFAULT_MTU
+            } else {
+                rx_mtu
+            },
+            mtu: DEFAULT_MTU,
+            security: SecurityLevel::Unencrypted,
+            authorized: false,
+            indication: None,
+        }
+    }
+
+    /// The connection this server answers on.
+    pub fn connection(&self) -> ConnectionId {
+        self.connection
+    }
+
+    /// The MTU negotiated with the client.
+    pub fn mtu(&self) -> u16 {
+        self.mtu
+    }
+
+    /// Updates the security of the link, EG after pairing completed.
+    pub fn set_security(&mut self, security: SecurityLevel) {
+        self.security = security;
+    }
+
+    /// Grants or revokes access to attributes which require authorization.
+    pub fn set_authorized(&mut self, authorized: bool) {
+        self.authorized = authorized;
+    }
+
+    /// Creates the server state for the connection of `transport`.
+    pub fn for_transport(transport: &impl AttTransport) -> Self {
+        let mut server = AttServer::new(transport.connection(), transport.mtu());
+        server.security = transport.security();
+        server
+    }
+
+    /// Receives a PDU from `transport`, processes it and sends the response back.
+    ///
+    /// `request` and `response` should hold at least [`AttTransport::mtu`] and
+    /// [`AttServer::mtu`] bytes. Returns `None` if no PDU arrived.
+    pub fn poll<D: Database, T: AttTransport>(
+        &mut self,
+        db: &mut D,
+        transport: &mut T,
+        request: &mut [u8],
+        response: &mut [u8],
+    ) -> Result<Option<Processed<D::Event>>, T::Error> {
+        let len = match transport.receive(request)? {
+            Some(len) => len,
+            None => return Ok(None),
+        };
+        self.security = transport.security();
+        let processed = self.process(db, &request[..len], response);
+        if let Some(len) = processed.response {
+            transport.send(&response[..len])?;
+        }
+        Ok(Some(processed))
+    }
+
+    /// Builds a Handle Value Notification of `value` for the attribute with `handle` in `pdu`.
+    ///
+    /// `configuration` is the client characteristic configuration the client wrote. The value
+    /// is truncated to the MTU. Returns the length of the PDU.
+    pub fn notification(
+        &self,
+        handle: u16,
+        value: &[u8],
+        configuration: &[u8],
+        pdu: &mut [u8],
+    ) -> Result<usize, NotifyError> {
+        if !enabled(configuration, NOTIFICATIONS_ENABLED) {
+            return Err(NotifyError::NotSubscribed);
+        }
+        Ok(self.handle_value(opcode::HANDLE_VALUE_NOTIFICATION, handle, value, pdu))
+    }
+
+    /// Builds a Handle Value Indication like [`AttServer::notification`].
+    ///
+    /// Only one indication may await its confirmation, which [`AttServer::process`] reports.
+    pub fn indication(
+        &mut self,
+        handle: u16,
+        value: &[u8],
+        configuration: &[u8],
+        pdu: &mut [u8],
+    ) -> Result<usize, NotifyError> {
+        if !enabled(configuration, INDICATIONS_ENABLED) {
+            return Err(NotifyError::NotSubscribed);
+        }
+        if self.indication.is_some() {
+            return Err(NotifyError::IndicationPending);
+        }
+        self.indication = Some(handle);
+        Ok(self.handle_value(opcode::HANDLE_VALUE_INDICATION, handle, value, pdu))
+    }
+
+    fn handle_value(&self, opcode: u8, handle: u16, value: &[u8], pdu: &mut [u8]) -> usize {
+        let limit = pdu.len().min(self.mtu as usize);
+        let mut out = Writer::new(&mut pdu[..limit]);
+        out.u8(opcode);
+        out.u16(handle);
+        out.truncated(value);
+        out.len
+    }
+
+    /// Handles one incoming PDU.
+    ///
+    /// The response is written to `response`, which should hold at least [`AttServer::mtu`]
+    /// bytes. Commands and malformed PDUs without an opcode have no response. Writes yield the
+    /// event of the database for the written attribute.
+    pub fn process<D: Database>(
+        &mut self,
+        db: &mut D,
+        request: &[u8],
+        response: &mut [u8],
+    ) -> Processed<D::Event> {
+        let (&opcode, params) = match request.split_first() {
+            Some(split) => split,
+            None => return Processed::nothing(),
+        };
+        let limit = response.len().min(self.mtu as usize);
+        let mut out = Writer::new(&mut response[..limit]);
+        let mut event = None;
+
+        let result = match opcode {
+            // The database keeps no values for the connection, so requests cannot be served.
+            // The writes of commands are refused by the database and dropped.
+            _ if usize::from(self.connection.0) >= db.connections()
+                && opcode & opcode::COMMAND_FLAG == 0
+                && opcode != opcode::HANDLE_VALUE_CONFIRMATION =>
+            {
+                Err((0, ErrorCode::UnlikelyError))
+            }
+            opcode::EXCHANGE_MTU_REQUEST => self.exchange_mtu(params, &mut out),
+            opcode::FIND_INFORMATION_REQUEST => self.find_information(db, params, &mut out),
+            opcode::FIND_BY_TYPE_VALUE_REQUEST => self.find_by_type_value(db, params, &mut out),
+            opcode::READ_BY_TYPE_REQUEST => self.read_by_type(db, params, &mut out),
+            opcode::READ_REQUEST => self.read(db, params, &mut out),
+            opcode::READ_BLOB_REQUEST => self.read_blob(db, params, &mut out),
+            opcode::READ_MULTIPLE_REQUEST => self.read_multiple(db, params, &mut out),
+            opcode::READ_BY_GROUP_TYPE_REQUEST => self.read_by_group_type(db, params, &mut out),
+            opcode::WRITE_REQUEST => self.write(db, params, Permissions::WRITE).map(|written| {
+                event = written;
+                out.u8(opcode::WRITE_RESPONSE);
+            }),
+            opcode::WRITE_COMMAND => {
+                return Processed {
+                    event: self
+                        .write(db, params, Permissions::WRITE_WITHOUT_RESPONSE)
+                        .ok()
+                        .flatten(),
+                    ..Processed::nothing()
+                };
+            }
+            opcode::HANDLE_VALUE_CONFIRMATION => {
+                return Processed {
+                    confirmed: self.indication.take(),
+                    ..Processed::nothing()
+                };
+            }
+            opcode if opcode & opcode::COMMAND_FLAG != 0 => return Processed::nothing(),
+            _ => Err((0, ErrorCode::RequestNotSupported)),
+        };
+
+        let response = match result {
+            Ok(()) => Some(out.len),
+            Err((handle, code)) => {
+                let mut out = Writer::new(response);
+                out.u8(opcode::ERROR_RESPONSE);
+                out.u8(opcode);
+                out.u16(handle);
+                out.u8(code.code());
+                Some(out.len)
+            }
+        };
+        Processed {
+            response,
+            event,
+            confirmed: None,
+        }
+    }
+
+    fn exchange_mtu(&mut self, params: &[u8], out: &mut Writer) -> Result<(), Failure> {
+        let client_mtu = u16_at(params, 0)?;
+        out.u8(opcode::EXCHANGE_MTU_RESPONSE);
+        out.u16(self.rx_mtu);
+        self.mtu = client_mtu.min(self.rx_mtu).max(DEFAULT_MTU);
+        Ok(())
+    }
+
+    fn find_information(
+        &self,
+        db: &impl Database,
+        params: &[u8],
+        out: &mut Writer,
+    ) -> Result<(), Failure> {
+        let (start, end) = handle_range(params)?;
+        out.u8(opcode::FIND_INFORMATION_RESPONSE);
+        // All entries of a response must use the same UUID format.
+        let mut format = None;
+        for index in indices(db, start, end) {
+            let att_type = db.att_type(index);
+            let len = att_type.encoded_len();
+            match format {
+                None => {
+                    out.u8(if len == 2 { 0x01 } else { 0x02 });
+                    format = Some(len);
+                }
+                Some(format) if format != len => break,
+                Some(_) => {}
+            }
+            if out.remaining() < 2 + len {
+                break;
+            }
+            out.u16(index as u16 + 1);
+            out.uuid(att_type);
+        }
+        if format.is_none() {
+            return Err((start, ErrorCode::AttributeNotFound));
+        }
+        Ok(())
+    }
+
+    fn find_by_type_value(
+        &self,
+        db: &impl Database,
+        params: &[u8],
+        out: &mut Writer,
+    ) -> Result<(), Failure> {
+        let (start, end) = handle_range(params)?;
+        let att_type = Uuid::Uuid16(u16_at(params, 4)?);
+        let value = &params[6..];
+        out.u8(opcode::FIND_BY_TYPE_VALUE_RESPONSE);
+        let mut found = false;
+        for index in indices(db, start, end) {
+            if !db.att_type(index).matches(&att_type) || db.value(self.connection, index) != value {
+                continue;
+            }
+            if out.remaining() < 4 {
+                break;
+            }
+            out.u16(index as u16 + 1);
+            out.u16(if is_service(att_type) {
+                group_end(db, index)
+            } else {
+                index as u16 + 1
+            });
+            found = true;
+        }
+        if !found {
+            return Err((start, ErrorCode::AttributeNotFound));
+        }
+        Ok(())
+    }
+
+    fn read_by_type(
+        &self,
+        db: &mut impl Database,
+        params: &[u8],
+        out: &mut Writer,
+    ) -> Result<(), Failure> {
+        let (start, end) = handle_range(params)?;
+        let att_type = Uuid::from_bytes(&params[4..]).ok_or((0, ErrorCode::InvalidPdu))?;
+        out.u8(opcode::READ_BY_TYPE_RESPONSE);
+        // The length of every handle value pair, which must be the same for all of them.
+        let mut entry_len = None;
+        for index in indices(db, start, end) {
+            if !db.att_type(index).matches(&att_type) {
+                continue;
+            }
+            if let Err(code) = self
+                .check_read(db.permissions(index))
+                .and_then(|_| db.prepare_read(index))
+            {
+                if entry_len.is_none() {
+                    return Err((index as u16 + 1, code));
+                }
+                break;
+            }
+            let value = db.value(self.connection, index);
+            // Only the first value may be truncated, the following ones have to fit as a whole.
+            let len = match entry_len {
+                None => {
+                    let len = value.len().min((out.remaining() - 3).min(253));
+                    out.u8(len as u8 + 2);
+                    entry_len = Some(len + 2);
+                    len
+                }
+                Some(entry_len) if entry_len != value.len() + 2 || out.remaining() < entry_len => {
+                    break
+                }
+                Some(_) => value.len(),
+            };
+            out.u16(index as u16 + 1);
+            out.slice(&value[..len]);
+        }
+        if entry_len.is_none() {
+            return Err((start, ErrorCode::AttributeNotFound));
+        }
+        Ok(())
+    }
+
+    fn read(&self, db: &mut impl Database, params: &[u8], out: &mut Writer) -> Result<(), Failure> {
+        let handle = u16_at(params, 0)?;
+        let index = self.readable(db, handle)?;
+        out.u8(opcode::READ_RESPONSE);
+        out.truncated(db.value(self.connection, index));
+        Ok(())
+    }
+
+    fn read_blob(
+        &self,
+        db: &mut impl Database,
+        params: &[u8],
+        out: &mut Writer,
+    ) -> Result<(), Failure> {
+        let handle = u16_at(params, 0)?;
+        let offset = u16_at(params, 2)? as usize;
+        let index = self.readable(db, handle)?;
+        let value = db.value(self.connection, index);
+        if offset > value.len() {
+            return Err((handle, ErrorCode::InvalidOffset));
+        }
+        out.u8(opcode::READ_BLOB_RESPONSE);
+        out.truncated(&value[offset..]);
+        Ok(())
+    }
+
+    fn read_multiple(
+        &self,
+        db: &mut impl Database,
+        params: &[u8],
+        out: &mut Writer,
+    ) -> Result<(), Failure> {
+        if params.len() < 4 || !params.len().is_multiple_of(2) {
+            return Err((0, ErrorCode::InvalidPdu));
+        }
+        // Check every handle before responding as any failure fails the whole request.
+        for handle in params.chunks(2) {
+            self.readable(db, u16::from_le_bytes([handle[0], handle[1]]))?;
+        }
+        out.u8(opcode::READ_MULTIPLE_RESPONSE);
+        for handle in params.chunks(2) {
+            let index = u16::from_le_bytes([handle[0], handle[1]]) as usize - 1;
+            out.truncated(db.value(self.connection, index));
+        }
+        Ok(())
+    }
+
+    fn read_by_group_type(
+        &self,
+        db: &impl Database,
+        params: &[u8],
+        out: &mut Writer,
+    ) -> Result<(), Failure> {
+        let (start, end) = handle_range(params)?;
+        let group_type = Uuid::from_bytes(&params[4..]).ok_or((0, ErrorCode::InvalidPdu))?;
+        if !is_service(group_type) {
+            return Err((start, ErrorCode::UnsupportedGroupType));
+        }
+        out.u8(opcode::READ_BY_GROUP_TYPE_RESPONSE);
+        let mut entry_len = None;
+        for index in indices(db, start, end) {
+            if !db.att_type(index).matches(&group_type) {
+                continue;
+            }
+            let value = db.value(self.connection, index);
+            // Only the first value may be truncated, the following ones have to fit as a whole.
+            let len = match entry_len {
+                None => {
+                    let len = value.len().min((out.remaining() - 5).min(251));
+                    out.u8(len as u8 + 4);
+                    entry_len = Some(len + 4);
+                    len
+                }
+                Some(entry_len) if entry_len != value.len() + 4 || out.remaining() < entry_len => {
+                    break
+                }
+                Some(_) => value.len(),
+            };
+            out.u16(index as u16 + 1);
+            out.u16(group_end(db, index));
+            out.slice(&value[..len]);
+        }
+        if entry_len.is_none() {
+            return Err((start, ErrorCode::AttributeNotFound));
+        }
+        Ok(())
+    }
+
+    /// Writes an attribute if it may be written with the request or command which needs the
+    /// `access` permission.
+    fn write<D: Database>(
+        &self,
+        db: &mut D,
+        params: &[u8],
+        access: Permissions,
+    ) -> Result<Option<D::Event>, Failure> {
+        let handle = u16_at(params, 0)?;
+        let index = Self::index(db, handle)?;
+        self.check_write(db.permissions(index), access)
+            .map_err(|code| (handle, code))?;
+        db.write(self.connection, index, &params[2..])
+            .map_err(|code| (handle, code))
+    }
+
+    /// Maps a handle to its attribute index.
+    fn index(db: &impl Database, handle: u16) -> Result<usize, Failure> {
+        if handle == 0 || handle as usize > db.attribute_count() {
+            return Err((handle, ErrorCode::InvalidHandle));
+        }
+        Ok(handle as usize - 1)
+    }
+
+    /// Maps a handle to its attribute index if the attribute may be read.
+    ///
+    /// The value is prepared for the read, so it is up to date afterwards.
+    fn readable(&self, db: &mut impl Database, handle: u16) -> Result<usize, Failure> {
+        let index = Self::index(db, handle)?;
+        self.check_read(db.permissions(index))
+            .and_then(|_| db.prepare_read(index))
+            .map_err(|code| (handle, code))?;
+        Ok(index)
+    }
+
+    fn check_read(&self, permissions: Permissions) -> Result<(), ErrorCode> {
+        if !permissions.contains(Permissions::READ) {
+            return Err(ErrorCode::ReadNotPermitted);
+        }
+        self.check_security(
+            permissions.contains(Permissions::READ_ENCRYPTED),
+            permissions.contains(Permissions::READ_AUTHENTICATED),
+            permissions.contains(Permissions::READ_AUTHORIZED),
+        )
+    }
+
+    fn check_write(&self, permissions: Permissions, access: Permissions) -> Result<(), ErrorCode> {
+        if !permissions.contains(access) {
+            return Err(ErrorCode::WriteNotPermitted);
+        }
+        self.check_security(
+            permissions.contains(Permissions::WRITE_ENCRYPTED),
+            permissions.contains(Permissions::WRITE_AUTHENTICATED),
+            permissions.contains(Permissions::WRITE_AUTHORIZED),
+        )
+    }
+
+    fn check_security(
+        &self,
+        encrypted: bool,
+        authenticated: bool,
+        authorized: bool,
+    ) -> Result<(), ErrorCode> {
+        if authenticated && self.security < SecurityLevel::Authenticated {
+            return Err(ErrorCode::InsufficientAuthentication);
+        }
+        if encrypted && self.security < SecurityLevel::Encrypted {
+            return Err(ErrorCode::InsufficientEncryption);
+        }
+        if authorized && !self.authorized {
+            return Err(ErrorCode::InsufficientAuthorization);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,466 @@
+//! The client side of the Attribute Protocol, which clients generated by the `gatt_client!`
+//! macro build on.
+
+use crate::{
+    att::{opcode, AttServer, ErrorCode, DEFAULT_MTU},
+    transport::AttTransport,
+    Database, Uuid,
+};
+
+/// The largest MTU ATT allows, which bounds the PDUs a client handles.
+pub const MAX_MTU: u16 = 517;
+
+/// Attribute type of a primary service declaration.
+const PRIMARY_SERVICE: u16 = 0x2800;
+/// Attribute type of a characteristic declaration.
+const CHARACTERISTIC: u16 = 0x2803;
+/// Attribute type of a client characteristic configuration descriptor.
+const CLIENT_CHARACTERISTIC_CONFIGURATION: Uuid = Uuid::Uuid16(0x2902);
+
+/// Carries the requests of a client to a server and the responses back.
+pub trait Exchange {
+    type Error;
+
+    /// Sends `request` and waits for the response, returns its length or `0` if none arrived.
+    fn request(&mut self, request: &[u8], response: &mut [u8]) -> Result<usize, Self::Error>;
+
+    /// Sends a PDU which gets no response, such as a Write Command or Handle Value Confirmation.
+    fn send(&mut self, pdu: &[u8]) -> Result<(), Self::Error>;
+}
+
+/// An [`Exchange`] which hands requests straight to a server in the same program, so host side
+/// tests can run a client against a server without a transport.
+pub struct Local<'a, D: Database> {
+    server: &'a mut AttServer,
+    db: &'a mut D,
+    event: Option<D::Event>,
+}
+
+impl<'a, D: Database> Local<'a, D> {
+    pub fn new(server: &'a mut AttServer, db: &'a mut D) -> Self {
+        Local {
+            server,
+            db,
+            event: None,
+        }
+    }
+
+    /// Takes the event of the last request which changed the database.
+    pub fn take_event(&mut self) -> Option<D::Event> {
+        self.event.take()
+    }
+}
+
+impl<D: Database> Exchange for Local<'_, D> {
+    type Error = core::convert::Infallible;
+
+    fn request(&mut self, request: &[u8], response: &mut [u8]) -> Result<usize, Self::Error> {
+        let processed = self.server.process(self.db, request, response);
+        if processed.event.is_some() {
+            self.event = processed.event;
+        }
+        Ok(processed.response.unwrap_or(0))
+    }
+
+    fn send(&mut self, pdu: &[u8]) -> Result<(), Self::Error> {
+        // Room for the Error Response to a request sent by mistake.
+        let mut response = [0; MAX_MTU as usize];
+        let mtu = self.server.mtu() as usize;
+        self.request(pdu, &mut response[..mtu]).map(|_| ())
+    }
+}
+
+/// An [`Exchange`] over the client's end of an [`AttTransport`], to a server in another program
+/// or device.
+///
+/// The next PDU the transport receives after a request is taken as its response, so the server
+/// should not notify or indicate while requests are underway.
+pub struct Remote<T> {
+    transport: T,
+}
+
+impl<T: AttTransport> Remote<T> {
+    pub fn new(transport: T) -> Self {
+        Remote { transport }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.transport
+    }
+}
+
+impl<T: AttTransport> Exchange for Remote<T> {
+    type Error = T::Error;
+
+    fn request(&mut self, request: &[u8], response: &mut [u8]) -> Result<usize, Self::Error> {
+        self.transport.send(request)?;
+        Ok(self.transport.receive(response)?.unwrap_or(0))
+    }
+
+    fn send(&mut self, pdu: &[u8]) -> Result<(), Self::Error> {
+        self.transport.send(pdu)
+    }
+}
+
+/// Why a request of a client failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClientError<E> {
+    /// The exchange failed to carry the request or response.
+    Exchange(E),
+    /// The server answered with an Error Response with `code` for `handle`.
+    Att { handle: u16, code: u8 },
+    /// Discovery found no service, characteristic or descriptor with the UUID.
+    NotFound(Uuid),
+    /// The server sent no response or one which does not answer the request.
+    UnexpectedResponse,
+    /// A value does not fit into a Write Request or Command at the current MTU.
+    ValueTooLong,
+    /// A value read from the server does not hold a valid encoding of its declared type.
+    InvalidValue,
+}
+
+impl<E> ClientError<E> {
+    /// Whether the server answered with the error `code`.
+    pub fn is(&self, code: ErrorCode) -> bool {
+        matches!(self, ClientError::Att { code: c, .. } if *c == code.code())
+    }
+}
+
+/// The handles a client discovered for a characteristic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Discovered {
+    /// The handle of the characteristic value.
+    pub value: u16,
+    /// The handle of the client characteristic configuration, if the characteristic has one.
+    pub configuration: Option<u16>,
+}
+
+fn u16_at(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+/// Builds requests and parses the responses to them.
+///
+/// The client holds no exchange itself, every request takes one, so a [`Local`] exchange can
+/// borrow the server only for as long as a request takes.
+#[derive(Debug)]
+pub struct AttClient {
+    mtu: u16,
+}
+
+impl Default for AttClient {
+    fn default() -> Self {
+        AttClient::new()
+    }
+}
+
+impl AttClient {
+    pub const fn new() -> Self {
+        AttClient { mtu: DEFAULT_MTU }
+    }
+
+    /// The MTU of the connection, which limits the length of requests and responses.
+    pub fn mtu(&self) -> u16 {
+        self.mtu
+    }
+
+    /// Sends `request` and returns the parameters of the response if it has the `expected`
+    /// opcode.
+    fn request<'r, X: Exchange>(
+        &self,
+        x: &mut X,
+        request: &[u8],
+        response: &'r mut [u8],
+        expected: u8,
+    ) -> Result<&'r [u8], ClientError<X::Error>> {
+        let limit = response.len().min(self.mtu as usize);
+        let response = &mut response[..limit];
+        let len = x
+            .request(request, response)
+            .map_err(ClientError::Exchange)?;
+        match response[..len].split_first() {
+            Some((&opcode, params)) if opcode == expected => Ok(params),
+            Some((&opcode::ERROR_RESPONSE, params))
+                if params.len() == 4 && params[0] == request[0] =>
+            {
+                Err(ClientError::Att {
+                    handle: u16_at(params, 1),
+                    code: params[3],
+                })
+            }
+            _ => Err(ClientError::UnexpectedResponse),
+        }
+    }
+
+    /// Negotiates the MTU, announcing `rx_mtu` as the largest PDU the client receives. Returns
+    /// the MTU of the connection.
+    pub fn exchange_mtu<X: Exchange>(
+        &mut self,
+        x: &mut X,
+        rx_mtu: u16,
+    ) -> Result<u16, ClientError<X::Error>> {
+        let rx_mtu = rx_mtu.clamp(DEFAULT_MTU, MAX_MTU);
+        let [low, high] = rx_mtu.to_le_bytes();
+        // Room for an Error Response as well.
+        let mut response = [0; 5];
+        let params = self.request(
+            x,
+            &[opcode::EXCHANGE_MTU_REQUEST, low, high],
+            &mut response,
+            opcode::EXCHANGE_MTU_RESPONSE,
+        )?;
+        if params.len() != 2 {
+            return Err(ClientError::UnexpectedResponse);
+        }
+        self.mtu = u16_at(params, 0).min(rx_mtu).max(DEFAULT_MTU);
+        Ok(self.mtu)
+    }
+
+    /// Finds the primary service with `uuid`, returns the first and last handle of its group.
+    pub fn find_service<X: Exchange>(
+        &self,
+        x: &mut X,
+        uuid: Uuid,
+    ) -> Result<(u16, u16), ClientError<X::Error>> {
+        let mut request = [0; 23];
+        request[0] = opcode::FIND_BY_TYPE_VALUE_REQUEST;
+        request[1..3].copy_from_slice(&1u16.to_le_bytes());
+        request[3..5].copy_from_slice(&u16::MAX.to_le_bytes());
+        request[5..7].copy_from_slice(&PRIMARY_SERVICE.to_le_bytes());
+        uuid.write_to(&mut request[7..]);
+        let mut response = [0; MAX_MTU as usize];
+        match self.request(
+            x,
+            &request[..7 + uuid.encoded_len()],
+            &mut response,
+            opcode::FIND_BY_TYPE_VALUE_RESPONSE,
+        ) {
+            Ok(params) if params.len() >= 4 => Ok((u16_at(params, 0), u16_at(params, 2))),
+            Ok(_) => Err(ClientError::UnexpectedResponse),
+            Err(error) if error.is(ErrorCode::AttributeNotFound) => {
+                Err(ClientError::NotFound(uuid))
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Finds the characteristic with `uuid` among the handles of `service` and its client
+    /// characteristic configuration.
+    pub fn find_characteristic<X: Exchange>(
+        &self,
+        x: &mut X,
+        service: (u16, u16),
+        uuid: Uuid,
+    ) -> Result<Discovered, ClientError<X::Error>> {
+        let (mut next, end) = service;
+        let mut value = None;
+        // The characteristic ends where the next declaration or the service does.
+        let mut last = end;
+        let mut response = [0; MAX_MTU as usize];
+        'requests: while next != 0 && next <= end {
+            let mut request = [opcode::READ_BY_TYPE_REQUEST, 0, 0, 0, 0, 0, 0];
+            request[1..3].copy_from_slice(&next.to_le_bytes());
+            request[3..5].copy_from_slice(&end.to_le_bytes());
+            request[5..7].copy_from_slice(&CHARACTERISTIC.to_le_bytes());
+            let params =
+                match self.request(x, &request, &mut response, opcode::READ_BY_TYPE_RESPONSE) {
+                    Err(error) if error.is(ErrorCode::AttributeNotFound) => break,
+                    result => result?,
+                };
+            let (len, entries) = match params.split_first() {
+                Some((&len, entries)) if len == 7 || len == 21 => (len as usize, entries),
+                _ => return Err(ClientError::UnexpectedResponse),
+            };
+            if entries.len() < len {
+                return Err(ClientError::UnexpectedResponse);
+            }
+            for entry in entries.chunks_exact(len) {
+                let handle = u16_at(entry, 0);
+                if handle < next {
+                    return Err(ClientError::UnexpectedResponse);
+                }
+                if value.is_some() {
+                    last = handle - 1;
+                    break 'requests;
+                }
+                if Uuid::from_bytes(&entry[5..]).is_some_and(|u| u.matches(&uuid)) {
+                    value = Some(u16_at(entry, 3));
+                }
+                next = handle.wrapping_add(1);
+            }
+        }
+        let value = value.ok_or(ClientError::NotFound(uuid))?;
+        let configuration = if value < last {
+            match self.find_descriptor(x, (value + 1, last), CLIENT_CHARACTERISTIC_CONFIGURATION) {
+                Ok(handle) => Some(handle),
+                Err(ClientError::NotFound(_)) => None,
+                Err(error) => return Err(error),
+            }
+        } else {
+            None
+        };
+        Ok(Discovered {
+            value,
+            configuration,
+        })
+    }
+
+    /// Finds the first attribute with the type `uuid` among the handles of `range`.
+    pub fn find_descriptor<X: Exchange>(
+        &self,
+        x: &mut X,
+        range: (u16, u16),
+        uuid: Uuid,
+    ) -> Result<u16, ClientError<X::Error>> {
+        let (mut next, end) = range;
+        let mut response = [0; MAX_MTU as usize];
+        while next != 0 && next <= end {
+            let mut request = [opcode::FIND_INFORMATION_REQUEST, 0, 0, 0, 0];
+            request[1..3].copy_from_slice(&next.to_le_bytes());
+            request[3..5].copy_from_slice(&end.to_le_bytes());
+            let params = match self.request(
+                x,
+                &request,
+                &mut response,
+                opcode::FIND_INFORMATION_RESPONSE,
+            ) {
+                Err(error) if error.is(ErrorCode::AttributeNotFound) => break,
+                result => result?,
+            };
+            let (len, entries) = match params.split_first() {
+                Some((0x01, entries)) => (4, entries),
+                Some((0x02, entries)) => (18, entries),
+                _ => return Err(ClientError::UnexpectedResponse),
+            };
+            if entries.len() < len {
+                return Err(ClientError::UnexpectedResponse);
+            }
+            for entry in entries.chunks_exact(len) {
+                let handle = u16_at(entry, 0);
+                if handle < next {
+                    return Err(ClientError::UnexpectedResponse);
+                }
+                if Uuid::from_bytes(&entry[2..]).is_some_and(|u| u.matches(&uuid)) {
+                    return Ok(handle);
+                }
+                next = handle.wrapping_add(1);
+            }
+        }
+        Err(ClientError::NotFound(uuid))
+    }
+
+    /// Reads the value of `handle` into `buffer` and returns its length, following up with Read
+    /// Blob Requests for values longer than a response.
+    ///
+    /// Values longer than `buffer` are cut short.
+    pub fn read<X: Exchange>(
+        &self,
+        x: &mut X,
+        handle: u16,
+        buffer: &mut [u8],
+    ) -> Result<usize, ClientError<X::Error>> {
+        let [low, high] = handle.to_le_bytes();
+        let mut response = [0; MAX_MTU as usize];
+        let mut part = self.request(
+            x,
+            &[opcode::READ_REQUEST, low, high],
+            &mut response,
+            opcode::READ_RESPONSE,
+        )?;
+        let mut len = 0;
+        loop {
+            let copied = part.len().min(buffer.len() - len);
+            buffer[len..len + copied].copy_from_slice(&part[..copied]);
+            len += copied;
+            // Only a response filled up to the MTU may have been cut short.
+            if part.len() < self.mtu as usize - 1 || len == buffer.len() {
+                return Ok(len);
+            }
+            let [offset_low, offset_high] = (len as u16).to_le_bytes();
+            part = match self.request(
+                x,
+                &[
+                    opcode::READ_BLOB_REQUEST,
+                    low,
+                    high,
+                    offset_low,
+                    offset_high,
+                ],
+                &mut response,
+                opcode::READ_BLOB_RESPONSE,
+            ) {
+                Err(error) if error.is(ErrorCode::AttributeNotLong) => return Ok(len),
+                result => result?,
+            };
+            if part.is_empty() {
+                return Ok(len);
+            }
+        }
+    }
+
+    /// Writes `value` to `handle` with a Write Request.
+    pub fn write<X: Exchange>(
+        &self,
+        x: &mut X,
+        handle: u16,
+        value: &[u8],
+    ) -> Result<(), ClientError<X::Error>> {
+        if 3 + value.len() > self.mtu as usize {
+            return Err(ClientError::ValueTooLong);
+        }
+        let mut request = [0; MAX_MTU as usize];
+        request[0] = opcode::WRITE_REQUEST;
+        request[1..3].copy_from_slice(&handle.to_le_bytes());
+        request[3..3 + value.len()].copy_from_slice(value);
+        let mut response = [0; 5];
+        self.request(
+            x,
+            &request[..3 + value.len()],
+            &mut response,
+            opcode::WRITE_RESPONSE,
+        )
+        .map(|_| ())
+    }
+
+    /// Writes `value` to `handle` with a Write Command, which the server does not answer.
+    pub fn write_command<X: Exchange>(
+        &self,
+        x: &mut X,
+        handle: u16,
+        value: &[u8],
+    ) -> Result<(), ClientError<X::Error>> {
+        if 3 + value.len() > self.mtu as usize {
+            return Err(ClientError::ValueTooLong);
+        }
+        let mut command = [0; MAX_MTU as usize];
+        command[0] = opcode::WRITE_COMMAND;
+        command[1..3].copy_from_slice(&handle.to_le_bytes());
+        command[3..3 + value.len()].copy_from_slice(value);
+        x.send(&command[..3 + value.len()])
+            .map_err(ClientError::Exchange)
+    }
+
+    /// Confirms an indication, so the server can send the next one.
+    pub fn confirm<X: Exchange>(&self, x: &mut X) -> Result<(), ClientError<X::Error>> {
+        x.send(&[opcode::HANDLE_VALUE_CONFIRMATION])
+            .map_err(ClientError::Exchange)
+    }
+}
+
+/// Splits a Handle Value Notification or Indication into the handle and value it carries, and
+/// whether it is an indication which needs to be confirmed.
+pub fn handle_value(pdu: &[u8]) -> Option<(u16, &[u8], bool)> {
+    match pdu {
+        [opcode, low, high, value @ ..]
+            if *opcode == opcode::HANDLE_VALUE_NOTIFICATION
+                || *opcode == opcode::HANDLE_VALUE_INDICATION =>
+        {
+            Some((
+                u16::from_le_bytes([*low, *high]),
+                value,
+                *opcode == opcode::HANDLE_VALUE_INDICATION,
+            ))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,79 @@
+/// A Rust type which attribute values are encoded as, in little endian byte order.
+pub trait GattValue: Sized {
+    /// The number of bytes of the encoded value.
+    const SIZE: usize;
+
+    /// Encodes the value into `buffer`, which is exactly `SIZE` bytes long.
+    fn to_bytes(&self, buffer: &mut [u8]);
+
+    /// Decodes a value from `buffer`, which is exactly `SIZE` bytes long.
+    ///
+    /// May panic if `buffer` does not pass [`GattValue::is_valid`].
+    fn from_bytes(buffer: &[u8]) -> Self;
+
+    /// Whether `buffer`, which is exactly `SIZE` bytes long, holds a valid encoding.
+    ///
+    /// Writes from clients which fail this check are rejected, EG unknown enum discriminants.
+    fn is_valid(_buffer: &[u8]) -> bool {
+        true
+    }
+
+    /// Whether `SIZE` zero bytes pass [`GattValue::is_valid`], as values without an initializer
+    /// start out zeroed. The `gatt_server!` macro rejects such values of types which set this to
+    /// `false`.
+    const ZEROED_IS_VALID: bool = true;
+}
+
+macro_rules! impl_numbers {
+    ($($t:ty),*) => {
+        $(
+            impl GattValue for $t {
+                const SIZE: usize = core::mem::size_of::<$t>();
+
+                fn to_bytes(&self, buffer: &mut [u8]) {
+                    buffer.copy_from_slice(&self.to_le_bytes());
+                }
+
+                fn from_bytes(buffer: &[u8]) -> Self {
+                    let mut bytes = [0; core::mem::size_of::<$t>()];
+                    bytes.copy_from_slice(buffer);
+                    <$t>::from_le_bytes(bytes)
+                }
+            }
+        )*
+    };
+}
+
+impl_numbers!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+impl GattValue for bool {
+    const SIZE: usize = 1;
+
+    fn to_bytes(&self, buffer: &mut [u8]) {
+        buffer[0] = *self as u8;
+    }
+
+    fn from_bytes(buffer: &[u8]) -> Self {
+        buffer[0] != 0
+    }
+}
+
+impl<T: GattValue, const N: usize> GattValue for [T; N] {
+    const SIZE: usize = T::SIZE * N;
+
+    fn to_bytes(&self, buffer: &mut [u8]) {
+        for (i, value) in self.iter().enumerate() {
+            value.to_bytes(&mut buffer[i * T::SIZE..(i + 1) * T::SIZE]);
+        }
+    }
+
+    fn from_bytes(buffer: &[u8]) -> Self {
+        core::array::from_fn(|i| T::from_bytes(&buffer[i * T::SIZE..(i + 1) * T::SIZE]))
+    }
+
+    fn is_valid(buffer: &[u8]) -> bool {
+        (0..N).all(|i| T::is_valid(&buffer[i * T::SIZE..(i + 1) * T::SIZE]))
+    }
+
+    const ZEROED_IS_VALID: bool = N == 0 || T::ZEROED_IS_VALID;
+}
@@ -0,0 +1,107 @@
+//! Runtime support for servers generated by the `gatt_server!` macro and clients generated by
+//! `gatt_client!`.
+//!
+//! The `std` feature adds a backend serving the database from Linux.
+#![no_std]
+
+#[cfg(feature = "std")]
+extern crate std;
+
+pub mod att;
+pub mod client;
+mod encoding;
+#[cfg(all(feature = "std", target_os = "linux"))]
+pub mod linux;
+mod store;
+mod table;
+pub mod transport;
+mod uuid;
+mod wrapper;
+
+#[doc(hidden)]
+pub use critical_section;
+pub use encoding::GattValue;
+pub use store::{Slot, Store, Value};
+pub use table::{Attribute, Characteristic, Descriptor, Service};
+pub use uuid::Uuid;
+pub use wrapper::Wrapper;
+
+/// The access and security requirements of an attribute.
+///
+/// The bits match the ones the `gatt_server!` macro emits for `permissions(...)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Permissions(u16);
+
+impl Permissions {
+    pub const READ: Permissions = Permissions(0x01);
+    pub const WRITE: Permissions = Permissions(0x02);
+    pub const READ_ENCRYPTED: Permissions = Permissions(0x04);
+    pub const READ_AUTHENTICATED: Permissions = Permissions(0x08);
+    pub const READ_AUTHORIZED: Permissions = Permissions(0x10);
+    pub const WRITE_ENCRYPTED: Permissions = Permissions(0x20);
+    pub const WRITE_AUTHENTICATED: Permissions = Permissions(0x40);
+    pub const WRITE_AUTHORIZED: Permissions = Permissions(0x80);
+    /// Writes with a Write Command, which gets no response. `WRITE` covers Write Requests.
+    pub const WRITE_WITHOUT_RESPONSE: Permissions = Permissions(0x100);
+    /// Writes with a Signed Write Command.
+    pub const SIGNED_WRITE: Permissions = Permissions(0x200);
+
+    pub const fn from_bits(bits: u16) -> Self {
+        Permissions(bits)
+    }
+
+    pub const fn bits(self) -> u16 {
+        self.0
+    }
+
+    /// Whether all bits of `other` are set.
+    pub const fn contains(self, other: Permissions) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+/// The attribute table an [`att::AttServer`] answers requests from.
+///
+/// Attributes are addressed by their index in the table. Handles are assigned without gaps, so
+/// the attribute at index `i` always has the handle `i + 1`.
+pub trait Database {
+    /// What the application learns about writes from clients.
+    type Event;
+
+    /// The number of connections the per connection values are kept for. [`att::AttServer`]
+    /// answers requests on connections from this number on with an `UnlikelyError`.
+    fn connections(&self) -> usize;
+
+    /// The number of attributes in the table.
+    fn attribute_count(&self) -> usize;
+
+    /// The type of the attribute at `index`.
+    fn att_type(&self, index: usize) -> Uuid;
+
+    /// The access and security requirements of the attribute at `index`.
+    fn permissions(&self, index: usize) -> Permissions;
+
+    /// Brings the value of the attribute at `index` up to date before a client reads it.
+    ///
+    /// An error is sent to the client instead of the value.
+    fn prepare_read(&mut self, index: usize) -> Result<(), att::ErrorCode>;
+
+    /// The current value of the attribute at `index` as the client on `connection` sees it.
+    fn value(&self, connection: att::ConnectionId, index: usize) -> &[u8];
+
+    /// Replaces the value of the attribute at `index` on behalf of the client on `connection`.
+    ///
+    /// Returns the event to hand to the application, if the attribute has one.
+    fn write(
+        &mut self,
+        connection: att::ConnectionId,
+        index: usize,
+        value: &[u8],
+    ) -> Result<Option<Self::Event>, att::ErrorCode>;
+
+    /// Forgets what the client on `connection` configured once it disconnected, so the next
+    /// client on the connection starts out fresh. Does nothing by default.
+    fn disconnect(&mut self, connection: att::ConnectionId) {
+        let _ = connection;
+    }
+}
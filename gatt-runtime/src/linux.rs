@@ -0,0 +1,288 @@
+//! Serving a database from Linux through the L2CAP sockets of BlueZ, to prototype firmware on a
+//! PC before flashing it.
+//!
+//! The kernel takes care of HCI and L2CAP, every packet on the ATT channel is one PDU. The socket
+//! layer is behind [`PduSocket`], so a socketpair can stand in for an adapter in tests.
+
+use std::{
+    io::{self, ErrorKind},
+    os::unix::io::{AsRawFd, FromRawFd, OwnedFd, RawFd},
+    vec,
+};
+
+use crate::{
+    att::{AttServer, ConnectionId, Processed, SecurityLevel},
+    transport::AttTransport,
+    Database,
+};
+
+/// The fixed L2CAP channel of ATT on LE links.
+const ATT_CID: u16 = 0x0004;
+const BTPROTO_L2CAP: libc::c_int = 0;
+const BDADDR_LE_PUBLIC: u8 = 0x01;
+const SOL_BLUETOOTH: libc::c_int = 274;
+const BT_SECURITY: libc::c_int = 4;
+const BT_SECURITY_MEDIUM: u8 = 2;
+const BT_SECURITY_HIGH: u8 = 3;
+
+/// `struct sockaddr_l2` of BlueZ.
+#[repr(C)]
+struct SockaddrL2 {
+    l2_family: libc::sa_family_t,
+    l2_psm: u16,
+    l2_bdaddr: [u8; 6],
+    l2_cid: u16,
+    l2_bdaddr_type: u8,
+}
+
+/// `struct bt_security` of BlueZ.
+#[repr(C)]
+#[derive(Default)]
+struct BtSecurity {
+    level: u8,
+    key_size: u8,
+}
+
+/// A socket which keeps the boundaries of the PDUs it carries.
+pub trait PduSocket {
+    /// Sends one PDU.
+    fn send(&mut self, pdu: &[u8]) -> io::Result<()>;
+
+    /// Blocks until a PDU arrives and returns its length, `0` once the peer disconnected.
+    fn receive(&mut self, buffer: &mut [u8]) -> io::Result<usize>;
+
+    /// The current security of the link.
+    fn security(&self) -> SecurityLevel;
+}
+
+/// Converts the `-1` error return of a libc call into the last OS error.
+fn check(result: libc::c_int) -> io::Result<libc::c_int> {
+    if result < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(result)
+    }
+}
+
+/// Retries a libc call returning a length as long as it is interrupted.
+fn retry(mut f: impl FnMut() -> libc::ssize_t) -> io::Result<usize> {
+    loop {
+        match f() {
+            len if len >= 0 => return Ok(len as usize),
+            _ => {
+                let error = io::Error::last_os_error();
+                if error.kind() != ErrorKind::Interrupted {
+                    return Err(error);
+                }
+            }
+        }
+    }
+}
+
+/// A connected `SOCK_SEQPACKET` socket, such as an accepted L2CAP ATT channel.
+pub struct SeqPacket(OwnedFd);
+
+impl SeqPacket {
+    /// A pair of connected Unix sockets, to stand in for a client and its L2CAP channel.
+    pub fn pair() -> io::Result<(SeqPacket, SeqPacket)> {
+        let mut fds = [0; 2];
+        check(unsafe {
+            libc::socketpair(
+                libc::AF_UNIX,
+                libc::SOCK_SEQPACKET | libc::SOCK_CLOEXEC,
+                0,
+                fds.as_mut_ptr(),
+            )
+        })?;
+        // Both descriptors were just created and are owned by nothing else.
+        unsafe {
+            Ok((
+                SeqPacket(OwnedFd::from_raw_fd(fds[0])),
+                SeqPacket(OwnedFd::from_raw_fd(fds[1])),
+            ))
+        }
+    }
+}
+
+impl AsRawFd for SeqPacket {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0.as_raw_fd()
+    }
+}
+
+impl PduSocket for SeqPacket {
+    fn send(&mut self, pdu: &[u8]) -> io::Result<()> {
+        let fd = self.as_raw_fd();
+        let len = retry(|| unsafe {
+            libc::send(fd, pdu.as_ptr().cast(), pdu.len(), libc::MSG_NOSIGNAL)
+        })?;
+        if len != pdu.len() {
+            return Err(io::Error::new(
+                ErrorKind::WriteZero,
+                "The PDU was cut short",
+            ));
+        }
+        Ok(())
+    }
+
+    fn receive(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        let fd = self.as_raw_fd();
+        retry(|| unsafe { libc::recv(fd, buffer.as_mut_ptr().cast(), buffer.len(), 0) })
+    }
+
+    /// Sockets without Bluetooth security, such as the socketpair stand-in, are unencrypted.
+    fn security(&self) -> SecurityLevel {
+        let mut security = BtSecurity::default();
+        let mut len = core::mem::size_of::<BtSecurity>() as libc::socklen_t;
+        let result = unsafe {
+            libc::getsockopt(
+                self.as_raw_fd(),
+                SOL_BLUETOOTH,
+                BT_SECURITY,
+                (&mut security as *mut BtSecurity).cast(),
+                &mut len,
+            )
+        };
+        match security.level {
+            _ if result < 0 => SecurityLevel::Unencrypted,
+            BT_SECURITY_MEDIUM => SecurityLevel::Encrypted,
+            level if level >= BT_SECURITY_HIGH => SecurityLevel::Authenticated,
+            _ => SecurityLevel::Unencrypted,
+        }
+    }
+}
+
+/// Listens for clients on the ATT channel of every LE adapter.
+pub struct L2capListener(OwnedFd);
+
+impl L2capListener {
+    /// Binds the ATT channel, which needs `CAP_NET_BIND_SERVICE` and BlueZ not serving GATT
+    /// itself.
+    pub fn bind() -> io::Result<Self> {
+        let fd = check(unsafe {
+            libc::socket(
+                libc::AF_BLUETOOTH,
+                libc::SOCK_SEQPACKET | libc::SOCK_CLOEXEC,
+                BTPROTO_L2CAP,
+            )
+        })?;
+        // The descriptor was just created and is owned by nothing else.
+        let listener = L2capListener(unsafe { OwnedFd::from_raw_fd(fd) });
+        let address = SockaddrL2 {
+            l2_family: libc::AF_BLUETOOTH as libc::sa_family_t,
+            l2_psm: 0,
+            l2_bdaddr: [0; 6],
+            l2_cid: ATT_CID.to_le(),
+            l2_bdaddr_type: BDADDR_LE_PUBLIC,
+        };
+        check(unsafe {
+            libc::bind(
+                fd,
+                (&address as *const SockaddrL2).cast(),
+                core::mem::size_of::<SockaddrL2>() as libc::socklen_t,
+            )
+        })?;
+        check(unsafe { libc::listen(fd, 1) })?;
+        Ok(listener)
+    }
+
+    /// Blocks until a client connects.
+    pub fn accept(&self) -> io::Result<SeqPacket> {
+        let fd = check(unsafe {
+            libc::accept4(
+                self.0.as_raw_fd(),
+                core::ptr::null_mut(),
+                core::ptr::null_mut(),
+                libc::SOCK_CLOEXEC,
+            )
+        })?;
+        // The descriptor was just accepted and is owned by nothing else.
+        Ok(SeqPacket(unsafe { OwnedFd::from_raw_fd(fd) }))
+    }
+}
+
+/// An [`AttTransport`] over a [`PduSocket`].
+///
+/// Receiving fails with [`ErrorKind::UnexpectedEof`] once the client disconnected.
+pub struct SocketTransport<S> {
+    socket: S,
+    connection: ConnectionId,
+    mtu: u16,
+}
+
+impl<S: PduSocket> SocketTransport<S> {
+    /// `mtu` is the largest PDU the server accepts from the client.
+    pub fn new(socket: S, connection: ConnectionId, mtu: u16) -> Self {
+        SocketTransport {
+            socket,
+            connection,
+            mtu,
+        }
+    }
+
+    pub fn into_inner(self) -> S {
+        self.socket
+    }
+}
+
+impl<S: PduSocket> AttTransport for SocketTransport<S> {
+    type Error = io::Error;
+
+    fn connection(&self) -> ConnectionId {
+        self.connection
+    }
+
+    fn mtu(&self) -> u16 {
+        self.mtu
+    }
+
+    fn security(&self) -> SecurityLevel {
+        self.socket.security()
+    }
+
+    fn send(&mut self, pdu: &[u8]) -> io::Result<()> {
+        self.socket.send(pdu)
+    }
+
+    fn receive(&mut self, buffer: &mut [u8]) -> io::Result<Option<usize>> {
+        match self.socket.receive(buffer)? {
+            0 => Err(ErrorKind::UnexpectedEof.into()),
+            len => Ok(Some(len)),
+        }
+    }
+}
+
+/// Answers the requests of the client on `transport` until it disconnects, then has the database
+/// forget what the client configured through [`Database::disconnect`].
+///
+/// Everything processing a PDU resulted in is handed to `on_processed`, such as the events of
+/// the client's writes, together with the server of the connection and the transport, so the
+/// client can be notified in turn. Errors of `on_processed` end the loop.
+pub fn serve<D: Database, S: PduSocket>(
+    db: &mut D,
+    transport: &mut SocketTransport<S>,
+    mut on_processed: impl FnMut(
+        &mut D,
+        &mut AttServer,
+        &mut SocketTransport<S>,
+        Processed<D::Event>,
+    ) -> io::Result<()>,
+) -> io::Result<()> {
+    let mut att = AttServer::for_transport(transport);
+    let mut request = vec![0; transport.mtu() as usize];
+    let mut response = vec![0; transport.mtu() as usize];
+    let result = loop {
+        match att.poll(db, transport, &mut request, &mut response) {
+            Ok(Some(processed)) => {
+                if let Err(error) = on_processed(db, &mut att, transport, processed) {
+                    break Err(error);
+                }
+            }
+            Ok(None) => {}
+            Err(error) if error.kind() == ErrorKind::UnexpectedEof => break Ok(()),
+            Err(error) => break Err(error),
+        }
+    };
+    db.disconnect(transport.connection());
+    result
+}
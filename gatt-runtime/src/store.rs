@@ -0,0 +1,205 @@
+use core::cell::UnsafeCell;
+
+use crate::att::{ConnectionId, ErrorCode};
+
+/// The mutable memory backing all attribute values which are not fixed at compile time.
+///
+/// The store is only ever accessed through the `GattServer` generated by `gatt_server!`, which
+/// exists once. Shared access to the server reads values, exclusive access writes them.
+pub struct Store<const N: usize>(UnsafeCell<[u8; N]>);
+
+// Access is serialized by the ownership of the generated `GattServer`.
+unsafe impl<const N: usize> Sync for Store<N> {}
+
+impl<const N: usize> Store<N> {
+    pub const fn new(init: [u8; N]) -> Self {
+        Store(UnsafeCell::new(init))
+    }
+
+    /// Reserves `len` bytes starting at `offset` for one attribute value.
+    ///
+    /// Panics if the range is out of bounds, which fails compilation when called in a static.
+    pub const fn value(&'static self, offset: usize, len: usize) -> Value {
+        assert!(offset + len <= N);
+        Value::Stored(Slot {
+            ptr: unsafe { (self.0.get() as *mut u8).add(offset) },
+            len,
+        })
+    }
+
+    /// Reserves `len` bytes for each of `C` connections starting at `offset`, to be used as a
+    /// [`Value::PerConnection`].
+    pub const fn values<const C: usize>(&'static self, offset: usize, len: usize) -> [Value; C] {
+        let mut values = [Value::Const(&[]); C];
+        let mut i = 0;
+        while i < C {
+            values[i] = self.value(offset + i * len, len);
+            i += 1;
+        }
+        values
+    }
+
+    /// Reserves a variable length value holding up to `capacity` bytes starting at `offset`.
+    ///
+    /// The current length is kept little endian in the two bytes before the value, so the range
+    /// spans `capacity + 2` bytes. Panics like [`Store::value`].
+    pub const fn variable(&'static self, offset: usize, capacity: usize) -> Value {
+        assert!(offset + 2 + capacity <= N);
+        assert!(capacity <= u16::MAX as usize);
+        Value::Variable(Slot {
+            ptr: unsafe { (self.0.get() as *mut u8).add(offset) },
+            len: capacity,
+        })
+    }
+}
+
+/// A range of bytes in a [`Store`].
+#[derive(Clone, Copy)]
+pub struct Slot {
+    ptr: *mut u8,
+    len: usize,
+}
+
+/// The value of an attribute.
+#[derive(Clone, Copy)]
+pub enum Value {
+    /// A value fixed at compile time, such as a declaration.
+    Const(&'static [u8]),
+    /// A value in a [`Store`].
+    Stored(Slot),
+    /// A value in a [`Store`] whose length may change up to the slot's capacity.
+    Variable(Slot),
+    /// A value every connection has its own copy of, such as a client characteristic
+    /// configuration. The copies are indexed by [`ConnectionId`] and reached through
+    /// [`Value::of`], the value itself holds no bytes.
+    PerConnection(&'static [Value]),
+}
+
+// Slots point into a `Store`, see its access rules.
+unsafe impl Sync for Slot {}
+
+impl Slot {
+    /// The bytes after the length of a variable length value.
+    unsafe fn data(&self) -> *mut u8 {
+        self.ptr.add(2)
+    }
+
+    /// The current length of a variable length value.
+    unsafe fn current_len(&self) -> usize {
+        u16::from_le_bytes([*self.ptr, *self.ptr.add(1)]) as usize
+    }
+}
+
+impl Value {
+    /// The value as the client on `connection` sees it.
+    ///
+    /// `None` if `connection` is not below the number of connections of a per connection value.
+    pub fn of(&self, connection: ConnectionId) -> Option<&Value> {
+        match self {
+            Value::PerConnection(values) => values.get(connection.0 as usize),
+            value => Some(value),
+        }
+    }
+
+    /// The largest number of bytes the value can hold.
+    pub fn capacity(&self) -> usize {
+        match self {
+            Value::Const(value) => value.len(),
+            Value::Stored(slot) | Value::Variable(slot) => slot.len,
+            Value::PerConnection(values) => values.first().map_or(0, Value::capacity),
+        }
+    }
+
+    /// The current bytes of the value.
+    ///
+    /// Variable length values only yield the bytes that were last written.
+    ///
+    /// # Safety
+    ///
+    /// The value must not be written while the returned slice is alive.
+    pub unsafe fn bytes(&self) -> &[u8] {
+        match self {
+            Value::Const(value) => value,
+            Value::Stored(slot) => core::slice::from_raw_parts(slot.ptr, slot.len),
+            Value::Variable(slot) => {
+                core::slice::from_raw_parts(slot.data(), slot.current_len().min(slot.len))
+            }
+            Value::PerConnection(_) => &[],
+        }
+    }
+
+    /// Checks whether `value` may replace the bytes of the value.
+    ///
+    /// Fixed size values must be replaced as a whole, variable length ones take anything up to
+    /// their capacity.
+    pub fn check(&self, value: &[u8]) -> Result<(), ErrorCode> {
+        match self {
+            Value::Const(_) => Err(ErrorCode::WriteNotPermitted),
+            Value::Stored(slot) if slot.len != value.len() => {
+                Err(ErrorCode::InvalidAttributeValueLength)
+            }
+            Value::Variable(slot) if slot.len < value.len() => {
+                Err(ErrorCode::InvalidAttributeValueLength)
+            }
+            Value::Stored(_) | Value::Variable(_) => Ok(()),
+            Value::PerConnection(_) => Err(ErrorCode::UnlikelyError),
+        }
+    }
+
+    /// Replaces the bytes of the value if [`Value::check`] allows it.
+    ///
+    /// # Safety
+    ///
+    /// No other reference to the value may be alive.
+    pub unsafe fn write(&self, value: &[u8]) -> Result<(), ErrorCode> {
+        self.check(value)?;
+        match self {
+            Value::Const(_) => Err(ErrorCode::WriteNotPermitted),
+            Value::PerConnection(_) => Err(ErrorCode::UnlikelyError),
+            Value::Stored(_) => self.update(|bytes| bytes.copy_from_slice(value)),
+            Value::Variable(slot) => {
+                let len = (value.len() as u16).to_le_bytes();
+                core::ptr::copy_nonoverlapping(len.as_ptr(), slot.ptr, 2);
+                core::ptr::copy_nonoverlapping(value.as_ptr(), slot.data(), value.len());
+                Ok(())
+            }
+        }
+    }
+
+    /// Zeroes the value, variable length values become empty.
+    ///
+    /// # Safety
+    ///
+    /// No other reference to the value may be alive.
+    pub unsafe fn clear(&self) {
+        match self {
+            Value::Const(_) => {}
+            Value::Stored(slot) => core::ptr::write_bytes(slot.ptr, 0, slot.len),
+            Value::Variable(slot) => core::ptr::write_bytes(slot.ptr, 0, 2),
+            Value::PerConnection(values) => values.iter().for_each(|value| value.clear()),
+        }
+    }
+
+    /// Modifies the bytes of the value in place.
+    ///
+    /// Variable length values are modified within their current length.
+    ///
+    /// # Safety
+    ///
+    /// No other reference to the value may be alive.
+    pub unsafe fn update(&self, f: impl FnOnce(&mut [u8])) -> Result<(), ErrorCode> {
+        match self {
+            Value::Const(_) => Err(ErrorCode::WriteNotPermitted),
+            Value::Stored(slot) => {
+                f(core::slice::from_raw_parts_mut(slot.ptr, slot.len));
+                Ok(())
+            }
+            Value::Variable(slot) => {
+                let len = slot.current_len().min(slot.len);
+                f(core::slice::from_raw_parts_mut(slot.data(), len));
+                Ok(())
+            }
+            Value::PerConnection(_) => Err(ErrorCode::UnlikelyError),
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use crate::{Permissions, Uuid, Value};
+
+/// One entry of the attribute table.
+pub struct Attribute {
+    /// The type of the attribute as a UUID, EG "Primary Service" or "Anaerobic Heart Rate Lower Limit"
+    pub att_type: Uuid,
+    /// Unique server-side identifer for attribute
+    pub handle: u16,
+    /// Access and security requirements
+    pub permissions: Permissions,
+    /// Attribute values can be any fixed length or variable length octet array, which if too large
+    /// can be sent across multiple PDUs
+    pub value: Value,
+}
+
+/// A descriptor and the attributes it is made of.
+pub struct Descriptor {
+    pub uuid: Uuid,
+    pub attributes: &'static [Attribute],
+}
+
+/// A characteristic, its attributes and descriptors.
+pub struct Characteristic {
+    pub uuid: Uuid,
+    /// The properties as they appear in the characteristic declaration
+    pub properties: u8,
+    /// The characteristic declaration followed by the value attributes
+    pub attributes: &'static [Attribute],
+    /// The descriptors including generated ones such as the client characteristic configuration
+    pub descriptors: &'static [Descriptor],
+}
+
+/// A service, its attributes and characteristics.
+pub struct Service {
+    pub uuid: Uuid,
+    /// The service declaration followed by the attributes outside of any characteristic
+    pub attributes: &'static [Attribute],
+    pub characteristics: &'static [Characteristic],
+}
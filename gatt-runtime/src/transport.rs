@@ -0,0 +1,163 @@
+//! The seam between an [`AttServer`](crate::att::AttServer) and the Bluetooth stack carrying
+//! its PDUs.
+
+use crate::att::{ConnectionId, SecurityLevel};
+
+/// A channel exchanging ATT PDUs with one client, such as the fixed L2CAP channel of a
+/// connection.
+pub trait AttTransport {
+    type Error;
+
+    /// The connection the PDUs are exchanged on.
+    fn connection(&self) -> ConnectionId;
+
+    /// The largest PDU the transport can receive, announced as the server's MTU.
+    fn mtu(&self) -> u16;
+
+    /// The current security of the link.
+    fn security(&self) -> SecurityLevel;
+
+    /// Sends one PDU to the client.
+    fn send(&mut self, pdu: &[u8]) -> Result<(), Self::Error>;
+
+    /// Receives the next PDU from the client into `buffer` and returns its length, `None` if
+    /// nothing arrived.
+    fn receive(&mut self, buffer: &mut [u8]) -> Result<Option<usize>, Self::Error>;
+}
+
+/// A [`Loopback`] had no room left for a PDU.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueueFull;
+
+/// PDUs queued back to back, each prefixed with its length.
+struct Queue<const N: usize> {
+    bytes: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> Queue<N> {
+    const fn new() -> Self {
+        Queue {
+            bytes: [0; N],
+            len: 0,
+        }
+    }
+
+    fn push(&mut self, pdu: &[u8]) -> Result<(), QueueFull> {
+        if self.len + 2 + pdu.len() > N || pdu.len() > u16::MAX as usize {
+            return Err(QueueFull);
+        }
+        self.bytes[self.len..self.len + 2].copy_from_slice(&(pdu.len() as u16).to_le_bytes());
+        self.bytes[self.len + 2..self.len + 2 + pdu.len()].copy_from_slice(pdu);
+        self.len += 2 + pdu.len();
+        Ok(())
+    }
+
+    /// Moves the oldest PDU into `buffer`, truncating it if the buffer is too small.
+    fn pop(&mut self, buffer: &mut [u8]) -> Option<usize> {
+        if self.len == 0 {
+            return None;
+        }
+        let pdu_len = u16::from_le_bytes([self.bytes[0], self.bytes[1]]) as usize;
+        let len = pdu_len.min(buffer.len());
+        buffer[..len].copy_from_slice(&self.bytes[2..2 + len]);
+        self.bytes.copy_within(2 + pdu_len..self.len, 0);
+        self.len -= 2 + pdu_len;
+        Some(len)
+    }
+}
+
+/// An in-memory transport which lets host side tests play the client of a server without a
+/// radio.
+///
+/// Each direction queues up to `N` bytes of PDUs.
+pub struct Loopback<const N: usize> {
+    connection: ConnectionId,
+    mtu: u16,
+    security: SecurityLevel,
+    to_server: Queue<N>,
+    to_client: Queue<N>,
+}
+
+impl<const N: usize> Loopback<N> {
+    pub const fn new(connection: ConnectionId, mtu: u16) -> Self {
+        Loopback {
+            connection,
+            mtu,
+            security: SecurityLevel::Unencrypted,
+            to_server: Queue::new(),
+            to_client: Queue::new(),
+        }
+    }
+
+    /// Changes the security of the link, as pairing would.
+    pub fn set_security(&mut self, security: SecurityLevel) {
+        self.security = security;
+    }
+
+    /// Queues a PDU from the client for the server.
+    pub fn send_to_server(&mut self, pdu: &[u8]) -> Result<(), QueueFull> {
+        self.to_server.push(pdu)
+    }
+
+    /// Takes the oldest PDU the server sent to the client.
+    pub fn receive_from_server(&mut self, buffer: &mut [u8]) -> Option<usize> {
+        self.to_client.pop(buffer)
+    }
+
+    /// The client's end of the loopback, for a [`Remote`](crate::client::Remote) exchange.
+    pub fn client(&mut self) -> LoopbackClient<'_, N> {
+        LoopbackClient(self)
+    }
+}
+
+impl<const N: usize> AttTransport for Loopback<N> {
+    type Error = QueueFull;
+
+    fn connection(&self) -> ConnectionId {
+        self.connection
+    }
+
+    fn mtu(&self) -> u16 {
+        self.mtu
+    }
+
+    fn security(&self) -> SecurityLevel {
+        self.security
+    }
+
+    fn send(&mut self, pdu: &[u8]) -> Result<(), QueueFull> {
+        self.to_client.push(pdu)
+    }
+
+    fn receive(&mut self, buffer: &mut [u8]) -> Result<Option<usize>, QueueFull> {
+        Ok(self.to_server.pop(buffer))
+    }
+}
+
+/// The client's end of a [`Loopback`], which sends to the server and receives from it.
+pub struct LoopbackClient<'a, const N: usize>(&'a mut Loopback<N>);
+
+impl<const N: usize> AttTransport for LoopbackClient<'_, N> {
+    type Error = QueueFull;
+
+    fn connection(&self) -> ConnectionId {
+        self.0.connection
+    }
+
+    fn mtu(&self) -> u16 {
+        self.0.mtu
+    }
+
+    fn security(&self) -> SecurityLevel {
+        self.0.security
+    }
+
+    fn send(&mut self, pdu: &[u8]) -> Result<(), QueueFull> {
+        self.0.send_to_server(pdu)
+    }
+
+    fn receive(&mut self, buffer: &mut [u8]) -> Result<Option<usize>, QueueFull> {
+        Ok(self.0.receive_from_server(buffer))
+    }
+}
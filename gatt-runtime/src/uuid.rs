@@ -0,0 +1,62 @@
+/// The Bluetooth base UUID `00000000-0000-1000-8000-00805F9B34FB` in little endian byte order.
+/// 16-bit UUIDs are aliases for this UUID with bytes 12 and 13 replaced.
+const BASE_UUID: [u8; 16] = [
+    0xFB, 0x34, 0x9B, 0x5F, 0x80, 0x00, 0x00, 0x80, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+/// The type of an attribute or the identifier of a service, characteristic or descriptor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Uuid {
+    /// A 16-bit Bluetooth SIG assigned UUID.
+    Uuid16(u16),
+    /// A 128-bit vendor UUID in little endian byte order, the order in which it goes over the air.
+    Uuid128([u8; 16]),
+}
+
+impl Uuid {
+    /// Parses a UUID as found in a PDU, which is either 2 or 16 bytes long.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        match bytes.len() {
+            2 => Some(Uuid::Uuid16(u16::from_le_bytes([bytes[0], bytes[1]]))),
+            16 => {
+                let mut uuid = [0; 16];
+                uuid.copy_from_slice(bytes);
+                Some(Uuid::Uuid128(uuid))
+            }
+            _ => None,
+        }
+    }
+
+    /// The number of bytes the UUID occupies in a PDU.
+    pub fn encoded_len(&self) -> usize {
+        match self {
+            Uuid::Uuid16(_) => 2,
+            Uuid::Uuid128(_) => 16,
+        }
+    }
+
+    /// Writes the little endian representation of the UUID to the start of `buffer`.
+    pub fn write_to(&self, buffer: &mut [u8]) {
+        match self {
+            Uuid::Uuid16(uuid) => buffer[..2].copy_from_slice(&uuid.to_le_bytes()),
+            Uuid::Uuid128(uuid) => buffer[..16].copy_from_slice(uuid),
+        }
+    }
+
+    /// The full 128-bit form of the UUID.
+    pub fn to_uuid128(&self) -> [u8; 16] {
+        match self {
+            Uuid::Uuid16(uuid) => {
+                let mut full = BASE_UUID;
+                full[12..14].copy_from_slice(&uuid.to_le_bytes());
+                full
+            }
+            Uuid::Uuid128(uuid) => *uuid,
+        }
+    }
+
+    /// Compares two UUIDs regardless of the form they are given in.
+    pub fn matches(&self, other: &Uuid) -> bool {
+        self.to_uuid128() == other.to_uuid128()
+    }
+}
@@ -0,0 +1,39 @@
+/// A `#[repr(transparent)]` newtype around one of the table types.
+///
+/// Every type named in `gatt_server!` wraps the table entry it stands for, so users can attach
+/// their own methods to it. Implement this with the [`wrapper!`](crate::wrapper) macro.
+///
+/// # Safety
+///
+/// `Self` must be `#[repr(transparent)]` with `T` as its only non zero-sized field.
+pub unsafe trait Wrapper<T>: Sized {
+    fn from_ref(inner: &T) -> &Self {
+        unsafe { &*(inner as *const T as *const Self) }
+    }
+
+    fn from_mut(inner: &mut T) -> &mut Self {
+        unsafe { &mut *(inner as *mut T as *mut Self) }
+    }
+}
+
+/// Declares newtypes for the services, characteristics, descriptors and attributes named in
+/// `gatt_server!`.
+///
+/// ```
+/// gatt_runtime::wrapper! {
+///     pub struct BatteryService(gatt_runtime::Service);
+///     pub struct BatteryLevel(gatt_runtime::Attribute);
+/// }
+/// ```
+#[macro_export]
+macro_rules! wrapper {
+    ($($(#[$meta:meta])* $vis:vis struct $name:ident($inner:ty);)*) => {
+        $(
+            $(#[$meta])*
+            #[repr(transparent)]
+            $vis struct $name($inner);
+
+            unsafe impl $crate::Wrapper<$inner> for $name {}
+        )*
+    };
+}
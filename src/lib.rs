@@ -1,641 +1,130 @@
 extern crate proc_macro;
-use std::{iter::FromIterator, ops::Range};
 
-use heck::{CamelCase, SnekCase};
-use itertools::Itertools;
 use proc_macro::TokenStream;
-use proc_macro2::Span;
-use quote::quote;
-use syn::{
-    braced,
-    parse::{Parse, ParseStream, Result},
-    parse_macro_input,
-    punctuated::Punctuated,
-    token::Brace,
-    token::Paren,
-    token::{self, Pub},
-    Error, Expr, ExprLit, ExprPath, Field, Fields, FieldsUnnamed, Ident, Lit, LitInt, LitStr, Path,
-    PathArguments, PathSegment, Token, Type, TypePath, VisPublic, Visibility,
-};
-
-#[derive(Debug)]
-struct GattServerParsed {
-    struct_likes: Vec<StructLike>,
-}
-
-#[derive(Debug)]
-enum Kind {
-    Service,
-    Characteristic,
-    Descriptor,
-    Attribute,
-}
-
-mod kw {
-    syn::custom_keyword!(service);
-    syn::custom_keyword!(characteristic);
-    syn::custom_keyword!(descriptor);
-    syn::custom_keyword!(attribute);
-}
-
-impl Parse for Kind {
-    fn parse(input: ParseStream) -> Result<Self> {
-        if input.peek(kw::service) {
-            input.parse::<Ident>()?;
-            Ok(Kind::Service)
-        } else if input.peek(kw::characteristic) {
-            input.parse::<Ident>()?;
-            Ok(Kind::Characteristic)
-        } else if input.peek(kw::descriptor) {
-            input.parse::<Ident>()?;
-            Ok(Kind::Descriptor)
-        } else if input.peek(kw::attribute) {
-            input.parse::<Ident>()?;
-            Ok(Kind::Attribute)
-        } else {
-            Err(input.error("Expected a valid path segment"))
-        }
-    }
-}
-
-#[derive(Debug)]
-struct StructLike {
-    kind: Kind,
-    name: Option<Ident>,
-    type_name: Path,
-    children: Vec<StructLike>,
-    size: LitInt,
-}
-
-impl Parse for StructLike {
-    fn parse(input: ParseStream) -> Result<Self> {
-        let kind = input.parse()?;
-        let name = if input.peek(Ident) {
-            Some(input.parse()?)
-        } else {
-            None
-        };
-        input.parse::<Token![:]>()?;
-        let type_name = input.parse()?;
-        let mut children = vec![];
-        let mut size = LitInt::new("0", Span::call_site());
-        if input.peek(token::Brace) {
-            let content;
-            braced!(content in input);
-            match kind {
-                Kind::Attribute => size = content.parse()?,
-                _ => {
-                    children = Punctuated::<StructLike, Token![,]>::parse_terminated(&content)?
-                        .into_iter()
-                        .collect()
-                }
-            }
-        }
-        Ok(StructLike {
-            kind,
-            name,
-            type_name,
-            children,
-            size,
-        })
-    }
-}
-
-impl Parse for GattServerParsed {
-    fn parse(input: ParseStream) -> Result<Self> {
-        Ok(GattServerParsed {
-            struct_likes: Punctuated::<StructLike, Token![,]>::parse_terminated(input)?
-                .into_iter()
-                .collect(),
-        })
-    }
-}
-
-#[derive(Debug)]
-struct Service {
-    attributes: Range<usize>,
-    characteristics: Range<usize>,
-    name: Option<Ident>,
-    type_name: Path,
-}
-
-#[derive(Debug)]
-struct Characteristic {
-    attributes: Range<usize>,
-    descriptors: Range<usize>,
-    name: Option<Ident>,
-    type_name: Path,
-}
-
-#[derive(Debug)]
-struct Descriptor {
-    attributes: Range<usize>,
-    name: Option<Ident>,
-    type_name: Path,
-}
-
-#[derive(Debug)]
-struct Attribute {
-    data: String,
-    size: usize,
-    name: Option<Ident>,
-    type_name: Path,
-}
-
-#[derive(Debug)]
-struct GattServer {
-    services: Vec<Service>,
-    characteristics: Vec<Characteristic>,
-    descriptors: Vec<Descriptor>,
-    attributes: Vec<Attribute>,
-}
-
-fn recurse_structs(server: &mut GattServer, input: &StructLike) {
-    match input.kind {
-        Kind::Service => {
-            let mut characteristics = vec![];
-            let mut attributes = vec![];
-            for child in &input.children {
-                match child.kind {
-                    Kind::Characteristic => characteristics.push(child),
-                    Kind::Attribute => attributes.push(child),
-                    _ => (), // TODO: Error
-                }
-            }
-            let cc = server.characteristics.len();
-            for c in &characteristics {
-                recurse_structs(server, &c);
-            }
-            let ac = server.attributes.len();
-            for a in &attributes {
-                recurse_structs(server, &a);
-            }
-            server.services.push(Service {
-                attributes: ac..ac + attributes.len(),
-                characteristics: cc..cc + characteristics.len(),
-                name: input.name.clone(),
-                type_name: input.type_name.clone(),
-            });
-        }
-        Kind::Characteristic => {
-            let mut descriptors = vec![];
-            let mut attributes = vec![];
-            for child in &input.children {
-                match child.kind {
-                    Kind::Descriptor => descriptors.push(child),
-                    Kind::Attribute => attributes.push(child),
-                    _ => (), // TODO: Error
-                }
-            }
-            let dc = server.descriptors.len();
-            for c in &descriptors {
-                recurse_structs(server, &c);
-            }
-            let ac = server.attributes.len();
-            for a in &attributes {
-                recurse_structs(server, &a);
-            }
-            server.characteristics.push(Characteristic {
-                attributes: ac..ac + attributes.len(),
-                descriptors: dc..dc + descriptors.len(),
-                name: input.name.clone(),
-                type_name: input.type_name.clone(),
-            });
-        }
-        Kind::Descriptor => {
-            let mut attributes = vec![];
-            for child in &input.children {
-                match child.kind {
-                    Kind::Attribute => attributes.push(child),
-                    _ => (), // TODO: Error
-                }
-            }
-            let ac = server.attributes.len();
-            for a in &attributes {
-                recurse_structs(server, &a);
-            }
-            server.descriptors.push(Descriptor {
-                attributes: ac..ac + attributes.len(),
-                name: input.name.clone(),
-                type_name: input.type_name.clone(),
-            });
-        }
-        Kind::Attribute => {
-            let attribute = Attribute {
-                data: input.type_name.get_ident().unwrap().to_string(),
-                size: input.size.base10_parse().unwrap(), // TODO: Get rid of unwrap.
-                name: input.name.clone(),
-                type_name: input.type_name.clone(),
-            };
-            if input.children.len() > 0 {
-                // TODO: Error
-            }
-            server.attributes.push(attribute);
-        }
-    }
-}
 
+/// Generates a `gatt_server` module holding the attribute table and typed handles to it.
+///
+/// ```text
+/// service: ServiceA uuid = 0x180F {
+///     characteristic: CharacteristicA uuid = "6e400001-b5a3-f393-e0a9-e50e24dcca9e"
+///         properties(read, write, notify) permissions(read, write_encrypted) {
+///         attribute: AttributeA<u16>,
+///     },
+///     attribute: AttributeB uuid = 0x2A00 { 3 },
+///     attribute: AttributeC uuid = 0x2A01 = b"Sensor-1",
+///     attribute: AttributeD uuid = 0x2A02 { max 20 } = "Living room",
+/// }
+/// ```
+///
+/// Services, characteristics and descriptors need a 16-bit SIG or 128-bit vendor UUID, unless
+/// their type is named after one the SIG assigned, such as `Battery`, `BatteryLevel` or
+/// `CharacteristicUserDescription`. The macro only bundles the assigned numbers of the common
+/// profiles. A leading `assigned_numbers = "path/to/assigned_numbers",` looks the UUIDs of other
+/// names up in a checkout of the SIG's assigned numbers, relative to the crate's `Cargo.toml`,
+/// whose `uuids` directory names them in words, `PnP ID` for a `PnpId`. Names in neither are an
+/// error without a `uuid = ...` clause. Named characteristics are
+/// checked against the Bluetooth specification: they default to its mandatory properties, may
+/// only have the properties it allows, and need the descriptors it requires. Their value is
+/// encoded in its format, so `characteristic: BatteryLevel` gets a `BatteryLevelValue<u8>`
+/// attribute generated, which has to be declared like any other. Values of varying size, such as
+/// strings, still need their attribute declared. A `uuid = ...` clause opts out of all this.
+/// Attributes take the UUID and permissions of their characteristic or descriptor unless they
+/// declare their own. The value of an attribute is encoded as the type in angle brackets, which
+/// implements `gatt_runtime::GattValue`, or as a byte array of the size in braces. A size of
+/// `{ max 32 }` makes the value variable length, holding anything up to 32 bytes.
+/// An `= ...` initializer with a byte string, string or integer literal sets the value the store
+/// starts out with and, without a size or type, gives the attribute the size of its bytes.
+/// Values without one start out zeroed, so types which reject zeros, such as enums without a zero
+/// discriminant, need an initializer.
+/// Attributes clients cannot write may share a type if they have the same value type or size.
+/// The handle type of a type used once has the `HANDLE` of its attribute.
+///
+/// Characteristics default to the `read` property and derive their permissions from their
+/// properties. The `write` permission only allows the writes the properties advertise, so a
+/// `write_without_response` characteristic rejects Write Requests. `authenticated_signed_writes`
+/// is not supported, as the server cannot verify signatures. The first attribute of a
+/// characteristic is its value. The characteristic declaration and, for characteristics which
+/// notify or indicate, the client characteristic configuration descriptor are generated.
+///
+/// `on_read = path::to::fn` calls `fn(&mut ValueHandle) -> Result<(), ErrorCode>` before a
+/// client reads the value, so it can be computed on demand. `on_write = path::to::fn` calls
+/// `fn(&mut ValueHandle, &[u8]) -> Result<(), ErrorCode>` with a well formed value before a
+/// client's write is stored. Errors are sent to the client and reject the write.
+///
+/// A leading `connections = 3,` sets how many clients are served at the same time, one by
+/// default. Client characteristic configurations are kept for every connection, which
+/// `ConnectionId`s index, and `GattServer::disconnect` clears them, also as
+/// `Database::disconnect`. Requests on connections from that number on are answered with an
+/// `UnlikelyError`.
+///
+/// `gatt_server::describe` writes a JSON description of the services, characteristics and
+/// descriptors with their UUIDs, handles, properties, permissions and value sizes to a
+/// `core::fmt::Write`. Trees loaded from a file are also described at build time by
+/// `gatt_build::export`, which writes the same JSON to `OUT_DIR` from a build script, so apps and
+/// documentation can be generated from it.
+///
+/// Handles of characteristics which notify or indicate have `notify` and `indicate` methods,
+/// which store a value and build the PDU announcing it to a subscribed client.
+///
+/// Writes of clients are reported as a `GattEvent` with a variant named after every attribute
+/// they may write, which `AttServer::process` returns.
+///
+/// `gatt_server!(file = "gatt.toml")` loads the tree from a TOML file relative to the crate's
+/// `Cargo.toml` instead. Every table has the clauses as keys and nests the tables of its children
+/// as `[[service.characteristic.attribute]]` and so on. Errors name the line of the table.
+///
+/// ```text
+/// connections = 2
+///
+/// [[service]]
+/// type = "ServiceA"
+/// uuid = 0x180F
+///
+/// [[service.characteristic]]
+/// type = "CharacteristicA"
+/// uuid = "6e400001-b5a3-f393-e0a9-e50e24dcca9e"
+/// properties = ["read", "write", "notify"]
+///
+/// [[service.characteristic.attribute]]
+/// type = "AttributeA"
+/// max = 20
+/// initial = "Living room"
+/// ```
+///
+/// The generated code refers to the `gatt_runtime` crate, which has to be a dependency. Every
+/// named type has to be a newtype around the matching `gatt_runtime` table type, declared with
+/// `gatt_runtime::wrapper!`.
 #[proc_macro]
 pub fn gatt_server(input: TokenStream) -> TokenStream {
-    let server_parsed = parse_macro_input!(input as GattServerParsed);
-
-    let mut server = GattServer {
-        services: vec![],
-        characteristics: vec![],
-        descriptors: vec![],
-        attributes: vec![],
-    };
-
-    for child in server_parsed.struct_likes {
-        recurse_structs(&mut server, &child);
-    }
-
-    let attribute_count = server.attributes.len();
-    let mut store_size = 0;
-
-    let attributes = server
-        .attributes
-        .iter()
-        .map(|a| {
-            let start = store_size;
-            let size = a.size;
-            store_size += a.size;
-            quote! {
-                Attribute {
-                    att_type: 0,
-                    handle: 0,
-                    value: unsafe { core::mem::transmute::<&'static u8, &'static [u8; #size]>(&DATA_STORE[#start]) }
-                }
-            }
-        })
-        .collect::<Vec<_>>();
-
-    let service_count = server.services.len();
-
-    let services = server
-        .services
-        .iter()
-        .map(|s| {
-            let a_start = s.attributes.start;
-            let a_end = s.attributes.end;
-            let c_start = s.characteristics.start;
-            let c_end = s.characteristics.end;
-            quote! {
-                Service {
-                    // attributes: &ATTRIBUTES[#a_start..#a_end],
-                    // characteristics: &CHARACTERISTICS[#c_start..#c_end]
-                    attributes: &[],
-                    characteristics: &[]
-                }
-            }
-        })
-        .collect::<Vec<_>>();
-
-    let characteristic_count = server.characteristics.len();
-
-    let characteristics = server
-        .characteristics
-        .iter()
-        .map(|s| {
-            let a_start = s.attributes.start;
-            let a_end = s.attributes.end;
-            let c_start = s.descriptors.start;
-            let c_end = s.descriptors.end;
-            quote! {
-                Characteristic {
-                    // attributes: &ATTRIBUTES[#a_start..#a_end],
-                    // descriptors: &DESCRIPTORS[#c_start..#c_end]
-                    attributes: &[],
-                    descriptors: &[]
-                }
-            }
-        })
-        .collect::<Vec<_>>();
-
-    let descriptor_count = server.descriptors.len();
-
-    let descriptors = server
-        .descriptors
-        .iter()
-        .map(|s| {
-            let a_start = s.attributes.start;
-            let a_end = s.attributes.end;
-            quote! {
-                Descriptor {
-                    // attributes: &ATTRIBUTES[#a_start..#a_end],
-                    attributes: &[],
-                }
-            }
-        })
-        .collect::<Vec<_>>();
-
-    let (service_getters, service_types) = server
-        .services
-        .iter()
-        .enumerate()
-        .map(|(i, s)| {
-            let fn_name = s
-                .name
-                .clone()
-                .or_else(|| {
-                    s.type_name
-                        .get_ident()
-                        .map(|i| Ident::new(&i.to_string().to_snek_case(), i.span()))
-                })
-                .unwrap();
-            let type_name = s.type_name.clone();
-            let handle_type_name = &mut s.type_name.clone();
-            let ident = &mut handle_type_name.segments.last_mut().unwrap().ident;
-            *ident = Ident::new(&(ident.to_string() + "Handle"), ident.span());
-
-            let (cfn_name, chandle_name) = server.characteristics[s.characteristics.clone()].iter().map(|c| {
-                let fn_name = c
-                    .name
-                    .clone()
-                    .or_else(|| {
-                        c.type_name
-                            .get_ident()
-                            .map(|i| Ident::new(&i.to_string().to_snek_case(), i.span()))
-                    })
-                    .unwrap();
-                let mut handle_type_name = c.type_name.clone();
-                let ident = &mut handle_type_name.segments.last_mut().unwrap().ident;
-                *ident = Ident::new(&(ident.to_string() + "Handle"), ident.span());
-                (fn_name, handle_type_name)
-            }).unzip::<_, _, Vec<_>, Vec<_>>();
-
-            (quote! {
-                pub fn #fn_name(&mut self) -> #handle_type_name {
-                    #handle_type_name {
-                        pd: core::marker::PhantomData {}
-                    }
-                }
-            },
-            quote! {
-                pub struct #handle_type_name<'a> {
-                    pd: core::marker::PhantomData<&'a mut ()>
-                }
-
-                impl core::ops::Deref for #handle_type_name<'_> {
-                    type Target = #type_name;
-
-                    fn deref(&self) -> &Self::Target {
-                        unsafe { core::mem::transmute(&SERVICES[#i]) }
-                    }
-                }
-
-                impl core::ops::DerefMut for #handle_type_name<'_> {
-                    fn deref_mut(&mut self) -> &mut Self::Target {
-                        unsafe {
-                            &mut *(core::mem::transmute::<&Service, &#type_name>(&SERVICES[0usize])
-                            as *const #type_name as *mut #type_name)
-                        }
-                    }
-                }
-
-                impl #handle_type_name<'_> {
-                    #(
-                        pub fn #cfn_name(&mut self) -> #chandle_name {
-                            #chandle_name {
-                                pd: core::marker::PhantomData {}
-                            }
-                        }
-                    )*
-                }
-            })
-        })
-        .unzip::<_, _, Vec<_>, Vec<_>>();
-
-    let characteristic_types = server
-        .characteristics
-        .iter()
-        .enumerate()
-        .map(|(i, s)| {
-            let fn_name = s
-                .name
-                .clone()
-                .or_else(|| {
-                    s.type_name
-                        .get_ident()
-                        .map(|i| Ident::new(&i.to_string().to_snek_case(), i.span()))
-                })
-                .unwrap();
-            let type_name = s.type_name.clone();
-            let handle_type_name = &mut s.type_name.clone();
-            let ident = &mut handle_type_name.segments.last_mut().unwrap().ident;
-            *ident = Ident::new(&(ident.to_string() + "Handle"), ident.span());
-
-            let (cfn_name, chandle_name) = server.descriptors[s.descriptors.clone()].iter().map(|c| {
-                let fn_name = c
-                    .name
-                    .clone()
-                    .or_else(|| {
-                        c.type_name
-                            .get_ident()
-                            .map(|i| Ident::new(&i.to_string().to_snek_case(), i.span()))
-                    })
-                    .unwrap();
-                let mut handle_type_name = c.type_name.clone();
-                let ident = &mut handle_type_name.segments.last_mut().unwrap().ident;
-                *ident = Ident::new(&(ident.to_string() + "Handle"), ident.span());
-                (fn_name, handle_type_name)
-            }).unzip::<_, _, Vec<_>, Vec<_>>();
-
-            quote! {
-                pub struct #handle_type_name<'a> {
-                    pd: core::marker::PhantomData<&'a mut ()>
-                }
-
-                impl core::ops::Deref for #handle_type_name<'_> {
-                    type Target = #type_name;
-
-                    fn deref(&self) -> &Self::Target {
-                        unsafe { core::mem::transmute(&CHARACTERISTICS[#i]) }
-                    }
-                }
-
-                impl core::ops::DerefMut for #handle_type_name<'_> {
-                    fn deref_mut(&mut self) -> &mut Self::Target {
-                        unsafe {
-                            &mut *(core::mem::transmute::<&Characteristic, &#type_name>(&CHARACTERISTICS[0usize])
-                            as *const #type_name as *mut #type_name)
-                        }
-                    }
-                }
-
-                impl #handle_type_name<'_> {
-                    #(
-                        pub fn #cfn_name(&mut self) -> #chandle_name {
-                            #chandle_name {
-                                pd: core::marker::PhantomData {}
-                            }
-                        }
-                    )*
-                }
-            }
-        })
-        .collect::<Vec<_>>();
-
-    let descriptor_types = server
-        .descriptors
-        .iter()
-        .enumerate()
-        .map(|(i, s)| {
-            let fn_name = s
-                .name
-                .clone()
-                .or_else(|| {
-                    s.type_name
-                        .get_ident()
-                        .map(|i| Ident::new(&i.to_string().to_snek_case(), i.span()))
-                })
-                .unwrap();
-            let type_name = s.type_name.clone();
-            let handle_type_name = &mut s.type_name.clone();
-            let ident = &mut handle_type_name.segments.last_mut().unwrap().ident;
-            *ident = Ident::new(&(ident.to_string() + "Handle"), ident.span());
-
-            let (cfn_name, chandle_name) = server.attributes[s.attributes.clone()].iter().map(|c| {
-                let fn_name = c
-                    .name
-                    .clone()
-                    .or_else(|| {
-                        c.type_name
-                            .get_ident()
-                            .map(|i| Ident::new(&i.to_string().to_snek_case(), i.span()))
-                    })
-                    .unwrap();
-                let mut handle_type_name = c.type_name.clone();
-                let ident = &mut handle_type_name.segments.last_mut().unwrap().ident;
-                *ident = Ident::new(&(ident.to_string() + "Handle"), ident.span());
-                (fn_name, handle_type_name)
-            }).unzip::<_, _, Vec<_>, Vec<_>>();
-
-            quote! {
-                pub struct #handle_type_name<'a> {
-                    pd: core::marker::PhantomData<&'a mut ()>
-                }
-
-                impl core::ops::Deref for #handle_type_name<'_> {
-                    type Target = #type_name;
-
-                    fn deref(&self) -> &Self::Target {
-                        unsafe { core::mem::transmute(&DESCRIPTORS[#i]) }
-                    }
-                }
-
-                impl core::ops::DerefMut for #handle_type_name<'_> {
-                    fn deref_mut(&mut self) -> &mut Self::Target {
-                        unsafe {
-                            &mut *(core::mem::transmute::<&Descriptor, &#type_name>(&DESCRIPTORS[0usize])
-                            as *const #type_name as *mut #type_name)
-                        }
-                    }
-                }
-
-                impl #handle_type_name<'_> {
-                    #(
-                        pub fn #cfn_name(&mut self) -> #chandle_name {
-                            #chandle_name {
-                                inner: unsafe { &mut *(&ATTRIBUTES[0usize] as *const Attribute as *mut Attribute) }
-                            }
-                        }
-                    )*
-                }
-            }
-        })
-        .collect::<Vec<_>>();
-
-    let attribute_types = server
-        .attributes
-        .iter()
-        .unique_by(|s| s.type_name.clone())
-        .map(|s| {
-            let fn_name = s
-                .name
-                .clone()
-                .or_else(|| {
-                    s.type_name
-                        .get_ident()
-                        .map(|i| Ident::new(&i.to_string().to_snek_case(), i.span()))
-                })
-                .unwrap();
-            let type_name = s.type_name.clone();
-            let handle_type_name = &mut s.type_name.clone();
-            let ident = &mut handle_type_name.segments.last_mut().unwrap().ident;
-            *ident = Ident::new(&(ident.to_string() + "Handle"), ident.span());
-
-            quote! {
-                pub struct #handle_type_name<'a> {
-                    inner: &'a mut Attribute,
-                }
-
-                impl core::ops::Deref for #handle_type_name<'_> {
-                    type Target = #type_name;
-
-                    fn deref(&self) -> &Self::Target {
-                        unsafe { core::mem::transmute(&self.inner) }
-                    }
-                }
-
-                impl core::ops::DerefMut for #handle_type_name<'_> {
-                    fn deref_mut(&mut self) -> &mut Self::Target {
-                        unsafe {
-                            &mut *(core::mem::transmute::<&Attribute, &#type_name>(&self.inner)
-                            as *const #type_name as *mut #type_name)
-                        }
-                    }
-                }
-
-                impl #handle_type_name<'_> {
-                    pub fn get(&self) -> &[u8] {
-                        &self.inner.value
-                    }
-
-                    pub fn set(&mut self, value: &[u8]) {
-                        unsafe { self.inner.value.copy_from_slice(value); }
-                    }
-                }
-            }
-        })
-        .collect::<Vec<_>>();
-
-    (quote! {
-        mod gatt_server {
-            use super::*;
-            static DATA_STORE: [u8; #store_size] = [0; #store_size];
-            static ATTRIBUTES: [Attribute; #attribute_count] = [#(#attributes,)*];
-            static SERVICES: [Service; #service_count] = [#(#services,)*];
-            static CHARACTERISTICS: [Characteristic; #characteristic_count] = [#(#characteristics,)*];
-            static DESCRIPTORS: [Descriptor; #descriptor_count] = [#(#descriptors,)*];
-            
-            static mut GAT_SERVER_TAKEN: bool = false;
-
-            pub struct GattServer {}
-
-            impl GattServer {
-                pub fn take() -> Option<Self> {
-                    // TODO:
-                    // cortex_m::interrupt::free(|_| {
-                        if unsafe { GAT_SERVER_TAKEN } {
-                            None
-                        } else {
-                            Some(GattServer {})
-                        }
-                    // })
-                }
-
-                #(#service_getters)*
-            }
-            
-            #(#service_types)*
-
-            #(#characteristic_types)*
+    gatt_codegen::gatt_server(input.into()).into()
+}
 
-            #(#descriptor_types)*
+/// Generates a `gatt_client` module with a typed `GattClient` for the tree `gatt_server!`
+/// takes, so a test tool or central can talk to a server declared in the same way.
+///
+/// `GattClient::discover` finds every service and characteristic on the server by its UUID.
+/// Characteristics get `read_*`, `write_*` and `write_*_without_response` methods for the
+/// properties they have, which encode and decode the value type of their first attribute, and
+/// `subscribe_*` for the ones which notify or indicate. Variable length values are read into and
+/// written from byte slices.
+/// `GattClient::notification` decodes the notifications and indications of subscribed values
+/// into a `Notification` with a variant named after every such characteristic.
+///
+/// Every request takes a `gatt_runtime::client::Exchange` carrying it to the server, `Local`
+/// for a server in the same program or `Remote` over an `AttTransport`. Clauses which only
+/// concern the server, such as `connections` and the callbacks, are ignored, and the named types
+/// of the tree need not exist.
+#[proc_macro]
+pub fn gatt_client(input: TokenStream) -> TokenStream {
+    gatt_codegen::gatt_client(input.into()).into()
+}
 
-            #(#attribute_types)*
-        }
-    })
-    .into()
+/// Derives `gatt_runtime::GattValue`, the little endian encoding of attribute values.
+///
+/// Structs encode their fields in declaration order. Enums without fields need an integer
+/// representation such as `#[repr(u8)]` and encode their discriminant. Types generated by
+/// `bitflags!` encode their bits and are marked with `#[gatt(bits = u8)]`.
+#[proc_macro_derive(GattValue, attributes(gatt))]
+pub fn derive_gatt_value(input: TokenStream) -> TokenStream {
+    gatt_codegen::derive_gatt_value(input.into()).into()
 }
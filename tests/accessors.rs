@@ -0,0 +1,169 @@
+use gatt::gatt_server;
+use gatt_runtime::{
+    att::ConnectionId, Attribute, Characteristic, Database, Descriptor, Service, Uuid,
+};
+
+gatt_runtime::wrapper! {
+    pub struct ServiceA(Service);
+    pub struct ServiceB(Service);
+    pub struct CharacteristicA(Characteristic);
+    pub struct CharacteristicB(Characteristic);
+    pub struct CharacteristicC(Characteristic);
+    pub struct DescriptorA(Descriptor);
+    pub struct DescriptorB(Descriptor);
+    pub struct ValueA(Attribute);
+    pub struct ValueB(Attribute);
+    pub struct ValueC(Attribute);
+    pub struct DescriptorValueA(Attribute);
+    pub struct DescriptorValueB(Attribute);
+    pub struct DescriptorValueC(Attribute);
+    pub struct ServiceValue(Attribute);
+}
+
+gatt_server! {
+    service: ServiceA uuid = 0x1801 {
+        characteristic: CharacteristicA uuid = 0x2A01 properties(read, notify) {
+            attribute: ValueA { 1 },
+            descriptor: DescriptorA uuid = 0x2901 {
+                attribute: DescriptorValueA { 2 },
+            },
+        },
+        characteristic: CharacteristicB uuid = 0x2A02 properties(read, write) {
+            attribute: ValueB { 2 },
+            descriptor: DescriptorB uuid = 0x2904 {
+                attribute: DescriptorValueB { 2 },
+                attribute c: DescriptorValueC { 2 },
+            },
+        },
+    },
+    service: ServiceB uuid = 0x1802 {
+        attribute: ServiceValue uuid = 0x2A00 { 1 },
+        characteristic: CharacteristicC uuid = 0x2A03 {
+            attribute: ValueC { 3 },
+        },
+    },
+}
+
+#[test]
+fn handles_follow_declaration_order() {
+    use gatt_server::*;
+
+    assert_eq!(ServiceAHandle::HANDLE, 1);
+    assert_eq!(CharacteristicAHandle::HANDLE, 2);
+    assert_eq!(ValueAHandle::HANDLE, 3);
+    // The synthesized client characteristic configuration descriptor comes first.
+    assert_eq!(DescriptorAHandle::HANDLE, 5);
+    assert_eq!(DescriptorValueAHandle::HANDLE, 5);
+    assert_eq!(CharacteristicBHandle::HANDLE, 6);
+    assert_eq!(ValueBHandle::HANDLE, 7);
+    assert_eq!(DescriptorBHandle::HANDLE, 8);
+    assert_eq!(DescriptorValueBHandle::HANDLE, 8);
+    assert_eq!(DescriptorValueCHandle::HANDLE, 9);
+    assert_eq!(ServiceBHandle::HANDLE, 10);
+    assert_eq!(ServiceValueHandle::HANDLE, 11);
+    assert_eq!(CharacteristicCHandle::HANDLE, 12);
+    assert_eq!(ValueCHandle::HANDLE, 13);
+}
+
+#[test]
+fn accessors_reach_their_own_entities() {
+    let mut server = gatt_server::GattServer::take().unwrap();
+
+    assert_eq!(server.service_a().0.uuid, Uuid::Uuid16(0x1801));
+    assert_eq!(server.service_b().0.uuid, Uuid::Uuid16(0x1802));
+    assert_eq!(
+        server.service_a().characteristic_b().0.uuid,
+        Uuid::Uuid16(0x2A02)
+    );
+    assert_eq!(
+        server.service_b().characteristic_c().0.uuid,
+        Uuid::Uuid16(0x2A03)
+    );
+    assert_eq!(
+        server.service_a().characteristic_b().descriptor_b().0.uuid,
+        Uuid::Uuid16(0x2904)
+    );
+
+    server.service_a().characteristic_a().value_a().set([1]);
+    server.service_a().characteristic_b().value_b().set([2, 2]);
+    server
+        .service_a()
+        .characteristic_a()
+        .descriptor_a()
+        .descriptor_value_a()
+        .set([3, 3]);
+    server
+        .service_a()
+        .characteristic_b()
+        .descriptor_b()
+        .descriptor_value_b()
+        .set([4, 4]);
+    server
+        .service_a()
+        .characteristic_b()
+        .descriptor_b()
+        .c()
+        .set([5, 5]);
+    server.service_b().service_value().set([6]);
+    server
+        .service_b()
+        .characteristic_c()
+        .value_c()
+        .set([7, 7, 7]);
+
+    let handle = |handle: u16| handle as usize - 1;
+    assert_eq!(server.value(ConnectionId(0), handle(3)), &[1]);
+    assert_eq!(server.value(ConnectionId(0), handle(4)), &[0, 0]);
+    assert_eq!(server.value(ConnectionId(0), handle(5)), &[3, 3]);
+    assert_eq!(server.value(ConnectionId(0), handle(7)), &[2, 2]);
+    assert_eq!(server.value(ConnectionId(0), handle(8)), &[4, 4]);
+    assert_eq!(server.value(ConnectionId(0), handle(9)), &[5, 5]);
+    assert_eq!(server.value(ConnectionId(0), handle(11)), &[6]);
+    assert_eq!(server.value(ConnectionId(0), handle(13)), &[7, 7, 7]);
+
+    tables_nest_their_children(&mut server);
+}
+
+/// The server can only be taken once per binary, so the accessor test hands it on.
+fn tables_nest_their_children(server: &mut gatt_server::GattServer) {
+    let service_a = server.service_a();
+
+    let uuids = service_a
+        .0
+        .characteristics
+        .iter()
+        .map(|c| c.uuid)
+        .collect::<Vec<_>>();
+    assert_eq!(uuids, [Uuid::Uuid16(0x2A01), Uuid::Uuid16(0x2A02)]);
+
+    let characteristic_a = &service_a.0.characteristics[0];
+    let handles = characteristic_a
+        .attributes
+        .iter()
+        .map(|a| a.handle)
+        .collect::<Vec<_>>();
+    assert_eq!(handles, [2, 3]);
+    let descriptors = characteristic_a
+        .descriptors
+        .iter()
+        .map(|d| (d.uuid, d.attributes[0].handle))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        descriptors,
+        [(Uuid::Uuid16(0x2902), 4), (Uuid::Uuid16(0x2901), 5)]
+    );
+
+    let characteristic_b = &service_a.0.characteristics[1];
+    assert_eq!(characteristic_b.descriptors[0].attributes.len(), 2);
+    assert_eq!(characteristic_b.descriptors[0].attributes[1].handle, 9);
+
+    let service_b = server.service_b();
+    let handles = service_b
+        .0
+        .attributes
+        .iter()
+        .map(|a| a.handle)
+        .collect::<Vec<_>>();
+    assert_eq!(handles, [10, 11]);
+    assert_eq!(service_b.0.characteristics.len(), 1);
+}
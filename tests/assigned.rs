@@ -0,0 +1,174 @@
+use gatt::gatt_server;
+use gatt_runtime::{
+    att::ConnectionId, Attribute, Characteristic, Database, Descriptor, Service, Uuid,
+};
+
+gatt_runtime::wrapper! {
+    pub struct Battery(Service);
+    pub struct BatteryLevel(Characteristic);
+    pub struct BatteryLevelValue(Attribute);
+    pub struct CharacteristicUserDescription(Descriptor);
+    pub struct Description(Attribute);
+    pub struct DeviceInformation(Service);
+    pub struct ManufacturerNameString(Characteristic);
+    pub struct Manufacturer(Attribute);
+    pub struct PnpId(Characteristic);
+    pub struct PnpIdValue(Attribute);
+    pub struct EnvironmentalSensing(Service);
+    pub struct Temperature(Characteristic);
+    pub struct TemperatureValue(Attribute);
+    pub struct HumanInterfaceDevice(Service);
+    pub struct Report(Characteristic);
+    pub struct ReportValue(Attribute);
+    pub struct ReportReference(Descriptor);
+    pub struct ReportReferenceValue(Attribute);
+}
+
+// Services, characteristics and descriptors named after the ones the SIG assigned need no UUID,
+// and the value of characteristics with a fixed format is generated.
+gatt_server! {
+    service: Battery {
+        characteristic: BatteryLevel properties(read, notify) {
+            descriptor: CharacteristicUserDescription {
+                attribute: Description = "Main cell",
+            },
+        },
+    },
+    service: DeviceInformation {
+        characteristic: ManufacturerNameString {
+            attribute: Manufacturer = "Acme",
+        },
+        characteristic: PnpId {
+            attribute: PnpIdValue = b"\x02\x5e\x04",
+        },
+    },
+    service: EnvironmentalSensing {
+        characteristic: Temperature {
+            attribute: TemperatureValue = -5,
+        },
+    },
+    service: HumanInterfaceDevice {
+        characteristic: Report properties(read, notify) {
+            attribute: ReportValue { max 8 },
+            descriptor: ReportReference {
+                attribute: ReportReferenceValue = b"\x01\x01",
+            },
+        },
+    },
+}
+
+#[test]
+fn assigned_numbers_fill_in_the_tree() {
+    let mut server = gatt_server::GattServer::take().unwrap();
+
+    assert_eq!(server.battery().0.uuid, Uuid::Uuid16(0x180F));
+    assert_eq!(
+        server.battery().battery_level().0.uuid,
+        Uuid::Uuid16(0x2A19)
+    );
+    // The battery level is a `u8`, which starts out cleared.
+    let level: u8 = server.battery().battery_level().battery_level_value().get();
+    assert_eq!(level, 0);
+    assert_eq!(
+        server
+            .battery()
+            .battery_level()
+            .characteristic_user_description()
+            .description()
+            .get(),
+        *b"Main cell"
+    );
+
+    assert_eq!(
+        server
+            .device_information()
+            .manufacturer_name_string()
+            .manufacturer()
+            .get(),
+        *b"Acme"
+    );
+    // A PnP ID has 7 bytes, so the initializer is padded.
+    assert_eq!(
+        server.device_information().pnp_id().pnp_id_value().get(),
+        [0x02, 0x5e, 0x04, 0, 0, 0, 0]
+    );
+
+    let temperature: i16 = server
+        .environmental_sensing()
+        .temperature()
+        .temperature_value()
+        .get();
+    assert_eq!(temperature, -5);
+
+    // Battery, level and its value, the generated client characteristic configuration and the
+    // user description.
+    let types = (0..5).map(|i| server.att_type(i)).collect::<Vec<_>>();
+    assert_eq!(
+        types,
+        [
+            Uuid::Uuid16(0x2800),
+            Uuid::Uuid16(0x2803),
+            Uuid::Uuid16(0x2A19),
+            Uuid::Uuid16(0x2902),
+            Uuid::Uuid16(0x2901),
+        ]
+    );
+    assert_eq!(
+        server.att_type(server.attribute_count() - 1),
+        Uuid::Uuid16(0x2908)
+    );
+}
+
+mod cycling {
+    use gatt::gatt_server;
+    use gatt_runtime::{Attribute, Characteristic, Descriptor, Service};
+
+    gatt_runtime::wrapper! {
+        pub struct CyclingSpeedAndCadence(Service);
+        pub struct CscFeature(Characteristic);
+        pub struct CscFeatureValue(Attribute);
+        pub struct SensorLocation(Characteristic);
+        pub struct SensorLocationValue(Attribute);
+        pub struct ValidRange(Descriptor);
+        pub struct ValidRangeValue(Attribute);
+    }
+
+    // Names beyond the bundled ones are looked up in the SIG's assigned numbers.
+    gatt_server! {
+        assigned_numbers = "tests/sig",
+        service: CyclingSpeedAndCadence {
+            characteristic: CscFeature {
+                attribute: CscFeatureValue<u16>,
+            },
+            characteristic: SensorLocation {
+                attribute: SensorLocationValue<u8>,
+                descriptor: ValidRange {
+                    attribute: ValidRangeValue = b"\x00\x10",
+                },
+            },
+        },
+    }
+
+    pub use self::gatt_server::GattServer;
+}
+
+#[test]
+fn assigned_numbers_are_loaded() {
+    let server = cycling::GattServer::take().unwrap();
+
+    let types = (0..server.attribute_count())
+        .map(|i| server.att_type(i))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        types,
+        [
+            Uuid::Uuid16(0x2800),
+            Uuid::Uuid16(0x2803),
+            Uuid::Uuid16(0x2A5C),
+            Uuid::Uuid16(0x2803),
+            Uuid::Uuid16(0x2A5D),
+            Uuid::Uuid16(0x2906),
+        ]
+    );
+    assert_eq!(server.value(ConnectionId(0), 0), &[0x16, 0x18]);
+}
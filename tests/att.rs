@@ -0,0 +1,156 @@
+use gatt::gatt_server;
+use gatt_runtime::{
+    att::{AttServer, ConnectionId},
+    Attribute, Characteristic, Service,
+};
+
+gatt_runtime::wrapper! {
+    pub struct Battery(Service);
+    pub struct Level(Characteristic);
+    pub struct LevelValue(Attribute);
+    pub struct Secret(Characteristic);
+    pub struct SecretValue(Attribute);
+    pub struct Vendor(Service);
+    pub struct Name(Characteristic);
+    pub struct NameValue(Attribute);
+}
+
+// Handles 1 to 6 are the battery service with the level, its client characteristic
+// configuration and the secret, 7 to 9 the vendor service with the name.
+gatt_server! {
+    service: Battery uuid = 0x180F {
+        characteristic: Level uuid = 0x2A19 properties(read, notify) {
+            attribute: LevelValue { 1 } = 100,
+        },
+        characteristic: Secret uuid = 0x2A1A permissions(read_encrypted) {
+            attribute: SecretValue { 1 },
+        },
+    },
+    service: Vendor uuid = "6e400001-b5a3-f393-e0a9-e50e24dcca9e" {
+        characteristic: Name uuid = "6e400002-b5a3-f393-e0a9-e50e24dcca9e" {
+            attribute: NameValue = b"abc",
+        },
+    },
+}
+
+/// The name UUID in the little endian order it goes over the air.
+const NAME_UUID: [u8; 16] = [
+    0x9e, 0xca, 0xdc, 0x24, 0x0e, 0xe5, 0xa9, 0xe0, 0x93, 0xf3, 0xa3, 0xb5, 0x02, 0x00, 0x40, 0x6e,
+];
+
+#[test]
+fn requests_get_their_exact_responses() {
+    let mut server = gatt_server::GattServer::take().unwrap();
+    let mut att = AttServer::new(ConnectionId(0), 23);
+    let mut response = [0; 23];
+    let mut exchange = |request: &[u8]| -> Option<Vec<u8>> {
+        att.process(&mut server, request, &mut response)
+            .response
+            .map(|len| response[..len].to_vec())
+    };
+
+    // Find Information stops when the response is full and when the UUID format changes.
+    assert_eq!(
+        exchange(&[0x04, 0x01, 0x00, 0xFF, 0xFF]).unwrap(),
+        [
+            0x05, 0x01, 0x01, 0x00, 0x00, 0x28, 0x02, 0x00, 0x03, 0x28, 0x03, 0x00, 0x19, 0x2A,
+            0x04, 0x00, 0x02, 0x29, 0x05, 0x00, 0x03, 0x28,
+        ]
+    );
+    let mut expected = vec![0x05, 0x02, 0x09, 0x00];
+    expected.extend_from_slice(&NAME_UUID);
+    assert_eq!(exchange(&[0x04, 0x09, 0x00, 0x09, 0x00]).unwrap(), expected);
+    assert_eq!(
+        exchange(&[0x04, 0x0A, 0x00, 0xFF, 0xFF]).unwrap(),
+        [0x01, 0x04, 0x0A, 0x00, 0x0A]
+    );
+
+    // Find By Type Value reports the end of the service group.
+    assert_eq!(
+        exchange(&[0x06, 0x01, 0x00, 0xFF, 0xFF, 0x00, 0x28, 0x0F, 0x18]).unwrap(),
+        [0x07, 0x01, 0x00, 0x06, 0x00]
+    );
+    assert_eq!(
+        exchange(&[0x06, 0x01, 0x00, 0xFF, 0xFF, 0x00, 0x28, 0x0A, 0x18]).unwrap(),
+        [0x01, 0x06, 0x01, 0x00, 0x0A]
+    );
+
+    // Read By Type only returns values of the same length as the first one.
+    assert_eq!(
+        exchange(&[0x08, 0x01, 0x00, 0xFF, 0xFF, 0x03, 0x28]).unwrap(),
+        [
+            0x09, 0x07, 0x02, 0x00, 0x12, 0x03, 0x00, 0x19, 0x2A, 0x05, 0x00, 0x02, 0x06, 0x00,
+            0x1A, 0x2A,
+        ]
+    );
+    assert_eq!(
+        exchange(&[0x08, 0x01, 0x00, 0xFF, 0xFF, 0x1A, 0x2A]).unwrap(),
+        [0x01, 0x08, 0x06, 0x00, 0x0F]
+    );
+    assert_eq!(
+        exchange(&[0x08, 0x01, 0x00, 0xFF, 0xFF, 0x99, 0x99]).unwrap(),
+        [0x01, 0x08, 0x01, 0x00, 0x0A]
+    );
+
+    // Read Multiple concatenates the values.
+    assert_eq!(
+        exchange(&[0x0E, 0x03, 0x00, 0x09, 0x00]).unwrap(),
+        [0x0F, 100, b'a', b'b', b'c']
+    );
+    assert_eq!(
+        exchange(&[0x0E, 0x03, 0x00, 0x20, 0x00]).unwrap(),
+        [0x01, 0x0E, 0x20, 0x00, 0x01]
+    );
+
+    malformed_requests_get_error_responses(&mut server);
+}
+
+/// The server can only be taken once per binary, so the first test hands it on.
+fn malformed_requests_get_error_responses(server: &mut gatt_server::GattServer) {
+    let mut att = AttServer::new(ConnectionId(0), 23);
+    let mut response = [0; 23];
+    let mut exchange = |request: &[u8]| -> Option<Vec<u8>> {
+        att.process(server, request, &mut response)
+            .response
+            .map(|len| response[..len].to_vec())
+    };
+
+    // Invalid handles.
+    assert_eq!(
+        exchange(&[0x0A, 0x00, 0x00]).unwrap(),
+        [0x01, 0x0A, 0x00, 0x00, 0x01]
+    );
+    assert_eq!(
+        exchange(&[0x0A, 0x20, 0x00]).unwrap(),
+        [0x01, 0x0A, 0x20, 0x00, 0x01]
+    );
+    assert_eq!(
+        exchange(&[0x04, 0x05, 0x00, 0x04, 0x00]).unwrap(),
+        [0x01, 0x04, 0x05, 0x00, 0x01]
+    );
+
+    // Invalid PDUs.
+    assert_eq!(
+        exchange(&[0x0A, 0x03]).unwrap(),
+        [0x01, 0x0A, 0x00, 0x00, 0x04]
+    );
+    assert_eq!(
+        exchange(&[0x08, 0x01, 0x00, 0xFF, 0xFF, 0x03]).unwrap(),
+        [0x01, 0x08, 0x00, 0x00, 0x04]
+    );
+    assert_eq!(
+        exchange(&[0x0E, 0x03, 0x00]).unwrap(),
+        [0x01, 0x0E, 0x00, 0x00, 0x04]
+    );
+
+    // Requests the server does not implement, such as Prepare Write.
+    assert_eq!(
+        exchange(&[0x16, 0x03, 0x00, 0x00, 0x00, 0x01]).unwrap(),
+        [0x01, 0x16, 0x00, 0x00, 0x06]
+    );
+
+    // Unknown commands and empty PDUs are dropped without a response.
+    assert_eq!(exchange(&[0xD2, 0x03, 0x00, 0x01]), None);
+    assert_eq!(exchange(&[0x7F]), None);
+    assert_eq!(exchange(&[]), None);
+}
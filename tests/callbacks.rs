@@ -0,0 +1,87 @@
+use gatt::gatt_server;
+use gatt_runtime::{
+    att::{AttServer, ConnectionId, ErrorCode},
+    Attribute, Characteristic, Database, Service,
+};
+
+gatt_runtime::wrapper! {
+    pub struct Counter(Service);
+    pub struct Count(Characteristic);
+    pub struct CountValue(Attribute);
+    pub struct Limit(Characteristic);
+    pub struct LimitValue(Attribute);
+}
+
+gatt_server! {
+    service: Counter uuid = 0x181C {
+        characteristic: Count uuid = 0x2AEA on_read = count_read {
+            attribute: CountValue<u32>,
+        },
+        characteristic: Limit uuid = 0x2AEB properties(read, write) on_write = limit_write {
+            attribute: LimitValue<u8> = 10,
+        },
+    },
+}
+
+/// Counts the reads of the value itself.
+fn count_read(count: &mut gatt_server::CountValueHandle) -> Result<(), ErrorCode> {
+    let reads = count.get() + 1;
+    if reads > 3 {
+        return Err(ErrorCode::Application(0x80));
+    }
+    count.set(reads);
+    Ok(())
+}
+
+/// Only accepts raising the limit.
+fn limit_write(limit: &mut gatt_server::LimitValueHandle, value: &[u8]) -> Result<(), ErrorCode> {
+    if value[0] < limit.get() {
+        return Err(ErrorCode::ValueNotAllowed);
+    }
+    Ok(())
+}
+
+#[test]
+fn callbacks_run_on_client_access() {
+    let mut server = gatt_server::GattServer::take().unwrap();
+    let mut att = AttServer::new(ConnectionId(0), 23);
+    let mut response = [0; 23];
+
+    // Every Read Request computes the value anew.
+    for reads in 1..=3u8 {
+        let len = att
+            .process(&mut server, &[0x0A, 0x03, 0x00], &mut response)
+            .response
+            .unwrap();
+        assert_eq!(&response[..len], &[0x0B, reads, 0, 0, 0]);
+    }
+    let len = att
+        .process(&mut server, &[0x0A, 0x03, 0x00], &mut response)
+        .response
+        .unwrap();
+    assert_eq!(&response[..len], &[0x01, 0x0A, 0x03, 0x00, 0x80]);
+
+    // Reading the value locally does not run the callback.
+    assert_eq!(server.counter().count().count_value().get(), 3);
+
+    let len = att
+        .process(&mut server, &[0x12, 0x05, 0x00, 20], &mut response)
+        .response
+        .unwrap();
+    assert_eq!(&response[..len], &[0x13]);
+    assert_eq!(server.value(ConnectionId(0), 4), &[20]);
+
+    let len = att
+        .process(&mut server, &[0x12, 0x05, 0x00, 5], &mut response)
+        .response
+        .unwrap();
+    assert_eq!(&response[..len], &[0x01, 0x12, 0x05, 0x00, 0x13]);
+    assert_eq!(server.value(ConnectionId(0), 4), &[20]);
+
+    // The length is checked before the callback sees the value.
+    let len = att
+        .process(&mut server, &[0x12, 0x05, 0x00], &mut response)
+        .response
+        .unwrap();
+    assert_eq!(&response[..len], &[0x01, 0x12, 0x05, 0x00, 0x0D]);
+}
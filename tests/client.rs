@@ -0,0 +1,143 @@
+use gatt::{gatt_client, gatt_server, GattValue};
+use gatt_runtime::{
+    att::{AttServer, ConnectionId},
+    client::{ClientError, Local},
+    Attribute, Characteristic, Service, Uuid,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, GattValue)]
+#[repr(u8)]
+pub enum Scale {
+    Celsius = 1,
+    Kelvin,
+}
+
+gatt_runtime::wrapper! {
+    pub struct Battery(Service);
+    pub struct Level(Characteristic);
+    pub struct LevelValue(Attribute);
+    pub struct Settings(Service);
+    pub struct Unit(Characteristic);
+    pub struct UnitValue(Attribute);
+    pub struct Label(Characteristic);
+    pub struct LabelValue(Attribute);
+    pub struct Reset(Characteristic);
+    pub struct ResetValue(Attribute);
+}
+
+/// The server and the client are generated from the same tree.
+macro_rules! sensor {
+    ($generate:ident) => {
+        $generate! {
+            service: Battery uuid = 0x180F {
+                characteristic: Level uuid = 0x2A19 properties(read, notify) {
+                    attribute: LevelValue<u8> = 100,
+                },
+            },
+            service: Settings uuid = "6e400001-b5a3-f393-e0a9-e50e24dcca9e" {
+                characteristic: Unit uuid = "6e400002-b5a3-f393-e0a9-e50e24dcca9e" properties(read, write) {
+                    attribute: UnitValue<Scale> = 1u8,
+                },
+                characteristic: Label uuid = "6e400003-b5a3-f393-e0a9-e50e24dcca9e" properties(read, write, indicate) {
+                    attribute: LabelValue { max 40 } = "Living room on the second floor",
+                },
+                characteristic: Reset uuid = "6e400004-b5a3-f393-e0a9-e50e24dcca9e" properties(write_without_response) {
+                    attribute: ResetValue<u8>,
+                },
+            },
+        }
+    };
+}
+
+sensor!(gatt_server);
+sensor!(gatt_client);
+
+mod missing {
+    pub use self::gatt_client::GattClient;
+
+    gatt::gatt_client! {
+        service: Environment uuid = 0x181A {
+            characteristic: Temperature uuid = 0x2A6E {
+                attribute: TemperatureValue<i16>,
+            },
+        },
+    }
+}
+
+#[test]
+fn client_talks_to_server() {
+    let mut server = gatt_server::GattServer::take().unwrap();
+    let mut att = AttServer::new(ConnectionId(0), 23);
+    let mut x = Local::new(&mut att, &mut server);
+
+    assert_eq!(
+        missing::GattClient::discover(&mut x, 23).err(),
+        Some(ClientError::NotFound(Uuid::Uuid16(0x181A)))
+    );
+
+    let client = gatt_client::GattClient::discover(&mut x, 23).unwrap();
+    assert_eq!(client.level.value, gatt_server::LevelValueHandle::HANDLE);
+    assert_eq!(client.level.configuration, Some(4));
+    assert_eq!(client.unit.value, gatt_server::UnitValueHandle::HANDLE);
+    assert_eq!(client.unit.configuration, None);
+    assert_eq!(client.label.value, gatt_server::LabelValueHandle::HANDLE);
+
+    assert_eq!(client.read_level(&mut x), Ok(100));
+    assert_eq!(client.read_unit(&mut x), Ok(Scale::Celsius));
+    client.write_unit(&mut x, Scale::Kelvin).unwrap();
+    assert!(matches!(
+        x.take_event(),
+        Some(gatt_server::GattEvent::UnitValue {
+            value: Scale::Kelvin,
+            ..
+        })
+    ));
+    assert_eq!(client.read_unit(&mut x), Ok(Scale::Kelvin));
+
+    // The label is longer than a Read Response at the default MTU.
+    let mut label = [0; 40];
+    let len = client.read_label(&mut x, &mut label).unwrap();
+    assert_eq!(&label[..len], b"Living room on the second floor");
+    assert_eq!(
+        client.write_label(&mut x, &[b'x'; 21]),
+        Err(ClientError::ValueTooLong)
+    );
+    client.write_label(&mut x, b"Kitchen").unwrap();
+    let len = client.read_label(&mut x, &mut label).unwrap();
+    assert_eq!(&label[..len], b"Kitchen");
+
+    // Characteristics which only take Write Commands are written without a response.
+    client.write_reset_without_response(&mut x, 7).unwrap();
+    assert!(matches!(
+        x.take_event(),
+        Some(gatt_server::GattEvent::ResetValue { value: 7, .. })
+    ));
+
+    client.subscribe_level(&mut x).unwrap();
+    client.subscribe_label(&mut x).unwrap();
+    let mut pdu = [0; 23];
+    let len = server.battery().level().notify(&att, 42, &mut pdu).unwrap();
+    assert!(matches!(
+        client.notification(&pdu[..len]),
+        Some(gatt_client::Notification::Level(42))
+    ));
+    let len = server
+        .settings()
+        .label()
+        .indicate(&mut att, b"Hall", &mut pdu)
+        .unwrap();
+    assert!(matches!(
+        client.notification(&pdu[..len]),
+        Some(gatt_client::Notification::Label(b"Hall"))
+    ));
+
+    // The server only sends the next indication once the client confirmed.
+    client
+        .confirm(&mut Local::new(&mut att, &mut server))
+        .unwrap();
+    assert!(server
+        .settings()
+        .label()
+        .indicate(&mut att, b"Attic", &mut pdu)
+        .is_ok());
+}
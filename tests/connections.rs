@@ -0,0 +1,101 @@
+use gatt::gatt_server;
+use gatt_runtime::{
+    att::{AttServer, ConnectionId, NotifyError},
+    Attribute, Characteristic, Database, Descriptor, Service,
+};
+
+gatt_runtime::wrapper! {
+    pub struct HeartRate(Service);
+    pub struct Measurement(Characteristic);
+    pub struct MeasurementValue(Attribute);
+    pub struct Alert(Characteristic);
+    pub struct AlertValue(Attribute);
+    pub struct AlertConfiguration(Descriptor);
+    pub struct AlertConfigurationValue(Attribute);
+}
+
+gatt_server! {
+    connections = 2,
+    service: HeartRate uuid = 0x180D {
+        characteristic: Measurement uuid = 0x2A37 properties(notify) {
+            attribute: MeasurementValue<u8>,
+        },
+        characteristic: Alert uuid = 0x2A06 properties(read, indicate) {
+            attribute: AlertValue<u8>,
+            descriptor: AlertConfiguration uuid = 0x2902 {
+                attribute: AlertConfigurationValue<u16>,
+            },
+        },
+    },
+}
+
+#[test]
+fn subscriptions_are_kept_per_connection() {
+    let mut server = gatt_server::GattServer::take().unwrap();
+    let mut first = AttServer::new(ConnectionId(0), 23);
+    let mut second = AttServer::new(ConnectionId(1), 23);
+    let mut response = [0; 23];
+    let mut pdu = [0; 23];
+
+    // Only the first client subscribes to the measurement.
+    let processed = first.process(&mut server, &[0x12, 0x04, 0x00, 0x01, 0x00], &mut response);
+    assert_eq!(
+        processed.response.map(|len| &response[..len]),
+        Some(&[0x13][..])
+    );
+    assert_eq!(server.value(ConnectionId(0), 3), &[0x01, 0x00]);
+    assert_eq!(server.value(ConnectionId(1), 3), &[0x00, 0x00]);
+
+    let processed = second.process(&mut server, &[0x0A, 0x04, 0x00], &mut response);
+    assert_eq!(
+        processed.response.map(|len| &response[..len]),
+        Some(&[0x0B, 0x00, 0x00][..])
+    );
+
+    let mut heart_rate = server.heart_rate();
+    assert_eq!(heart_rate.measurement().notify(&first, 72, &mut pdu), Ok(4));
+    assert_eq!(
+        heart_rate.measurement().notify(&second, 72, &mut pdu),
+        Err(NotifyError::NotSubscribed)
+    );
+
+    // Declared configurations are kept per connection as well.
+    second.process(&mut server, &[0x12, 0x07, 0x00, 0x02, 0x00], &mut response);
+    let mut heart_rate = server.heart_rate();
+    let mut alert = heart_rate.alert();
+    let mut descriptor = alert.alert_configuration();
+    let configuration = descriptor.alert_configuration_value();
+    assert_eq!(configuration.get(ConnectionId(0)), Some(0));
+    assert_eq!(configuration.get(ConnectionId(1)), Some(2));
+    assert_eq!(configuration.get(ConnectionId(2)), None);
+    assert_eq!(
+        alert.indicate(&mut first, 1, &mut pdu),
+        Err(NotifyError::NotSubscribed)
+    );
+    assert_eq!(alert.indicate(&mut second, 1, &mut pdu), Ok(4));
+
+    // A disconnect only forgets the subscriptions of its own connection.
+    server.disconnect(ConnectionId(1));
+    assert_eq!(server.value(ConnectionId(0), 3), &[0x01, 0x00]);
+    assert_eq!(server.value(ConnectionId(1), 6), &[0x00, 0x00]);
+
+    // Connections beyond the two the server keeps values for are refused instead of panicking.
+    let mut third = AttServer::new(ConnectionId(2), 23);
+    let processed = third.process(&mut server, &[0x0A, 0x04, 0x00], &mut response);
+    assert_eq!(
+        processed.response.map(|len| &response[..len]),
+        Some(&[0x01, 0x0A, 0x00, 0x00, 0x0E][..])
+    );
+    let processed = third.process(&mut server, &[0x52, 0x04, 0x00, 0x01, 0x00], &mut response);
+    assert_eq!(processed.response, None);
+    assert!(processed.event.is_none());
+    assert_eq!(server.value(ConnectionId(2), 3), &[]);
+    assert_eq!(
+        server
+            .heart_rate()
+            .measurement()
+            .notify(&third, 72, &mut pdu),
+        Err(NotifyError::NotSubscribed)
+    );
+    server.disconnect(ConnectionId(2));
+}
@@ -0,0 +1,160 @@
+use gatt::{gatt_server, GattValue};
+use gatt_runtime::{
+    att::{AttServer, ConnectionId},
+    Attribute, Characteristic, Database, GattValue, Service,
+};
+
+bitflags::bitflags! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, GattValue)]
+    #[gatt(bits = u8)]
+    pub struct Status: u8 {
+        const CALIBRATED = 0x01;
+        const LOW_BATTERY = 0x02;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, GattValue)]
+#[repr(u8)]
+pub enum Unit {
+    Celsius = 1,
+    Fahrenheit,
+    Kelvin = 10,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, GattValue)]
+#[repr(u16, align(4))]
+pub enum Mode {
+    Off,
+    Eco = 0x0102,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, GattValue)]
+#[repr(align(2), i8)]
+pub enum Offset {
+    Below = -1,
+    Above = 1,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, GattValue)]
+pub struct Timestamp(u32);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, GattValue)]
+pub struct Measurement {
+    value: i16,
+    unit: Unit,
+    status: Status,
+    timestamp: Timestamp,
+    history: [i16; 2],
+}
+
+gatt_runtime::wrapper! {
+    pub struct Environment(Service);
+    pub struct Temperature(Characteristic);
+    pub struct TemperatureValue(Attribute);
+    pub struct Configuration(Characteristic);
+    pub struct ConfigurationValue(Attribute);
+    pub struct Power(Characteristic);
+    pub struct PowerValue(Attribute);
+}
+
+gatt_server! {
+    service: Environment uuid = 0x181A {
+        // Units have no zero discriminant, so the values holding them need an initializer.
+        characteristic: Temperature uuid = 0x2A6E {
+            attribute: TemperatureValue<Measurement> = b"\0\0\x01\0\0\0\0\0\0\0\0\0",
+        },
+        characteristic: Configuration uuid = 0x2A6F properties(read, write) {
+            attribute: ConfigurationValue<Unit> = 1u8,
+        },
+        characteristic: Power uuid = "6e400001-b5a3-f393-e0a9-e50e24dcca9e" properties(read, write) {
+            attribute: PowerValue<Mode>,
+        },
+    },
+}
+
+#[test]
+fn sizes_add_up() {
+    assert_eq!(Status::SIZE, 1);
+    assert_eq!(Unit::SIZE, 1);
+    assert_eq!(Mode::SIZE, 2);
+    assert_eq!(Offset::SIZE, 1);
+    assert_eq!(Timestamp::SIZE, 4);
+    assert_eq!(Measurement::SIZE, 12);
+    assert_eq!(<[Measurement; 3]>::SIZE, 36);
+}
+
+#[test]
+fn values_round_trip() {
+    let measurement = Measurement {
+        value: -300,
+        unit: Unit::Fahrenheit,
+        status: Status::CALIBRATED | Status::LOW_BATTERY,
+        timestamp: Timestamp(0x01020304),
+        history: [1, -1],
+    };
+    let mut buffer = [0; 12];
+    measurement.to_bytes(&mut buffer);
+    assert_eq!(
+        buffer,
+        [0xD4, 0xFE, 2, 0x03, 0x04, 0x03, 0x02, 0x01, 0x01, 0x00, 0xFF, 0xFF]
+    );
+    assert!(Measurement::is_valid(&buffer));
+    assert_eq!(Measurement::from_bytes(&buffer), measurement);
+
+    assert_eq!(Unit::from_bytes(&[10]), Unit::Kelvin);
+    assert!(!Unit::is_valid(&[3]));
+    assert_eq!(Mode::from_bytes(&[0x02, 0x01]), Mode::Eco);
+    assert_eq!(Offset::from_bytes(&[0xFF]), Offset::Below);
+    assert!(!Offset::is_valid(&[0]));
+    assert!(!Measurement::is_valid(&[
+        0, 0, 3, 0, 0, 0, 0, 0, 0, 0, 0, 0
+    ]));
+}
+
+#[test]
+fn invalid_writes_are_rejected() {
+    let mut server = gatt_server::GattServer::take().unwrap();
+    let mut att = AttServer::new(ConnectionId(0), 23);
+    let mut response = [0; 23];
+
+    // Fresh values decode to their initializer or, without one, to all zeros.
+    assert_eq!(
+        server
+            .environment()
+            .temperature()
+            .temperature_value()
+            .get()
+            .unit,
+        Unit::Celsius
+    );
+    assert_eq!(
+        server
+            .environment()
+            .configuration()
+            .configuration_value()
+            .get(),
+        Unit::Celsius
+    );
+    assert_eq!(server.environment().power().power_value().get(), Mode::Off);
+
+    let len = att
+        .process(&mut server, &[0x12, 0x05, 0x00, 0x03], &mut response)
+        .response;
+    assert_eq!(
+        len.map(|len| &response[..len]),
+        Some(&[0x01, 0x12, 0x05, 0x00, 0x13][..])
+    );
+    let len = att
+        .process(&mut server, &[0x12, 0x05, 0x00, 0x0A], &mut response)
+        .response;
+    assert_eq!(len.map(|len| &response[..len]), Some(&[0x13][..]));
+    assert_eq!(
+        server
+            .environment()
+            .configuration()
+            .configuration_value()
+            .get(),
+        Unit::Kelvin
+    );
+    assert_eq!(server.value(ConnectionId(0), 4), &[0x0A]);
+}
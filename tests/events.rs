@@ -0,0 +1,95 @@
+use gatt::gatt_server;
+use gatt_runtime::{
+    att::{AttServer, ConnectionId},
+    Attribute, Characteristic, Service,
+};
+
+gatt_runtime::wrapper! {
+    pub struct Lights(Service);
+    pub struct Brightness(Characteristic);
+    pub struct BrightnessValue(Attribute);
+    pub struct Label(Characteristic);
+    pub struct LabelValue(Attribute);
+    pub struct Model(Characteristic);
+    pub struct ModelValue(Attribute);
+}
+
+gatt_server! {
+    connections = 8,
+    service: Lights uuid = 0x1815 {
+        characteristic: Brightness uuid = 0x2B01 properties(read, write) {
+            attribute: BrightnessValue<u16>,
+        },
+        characteristic: Label uuid = 0x2B02 properties(read, write_without_response) {
+            attribute: LabelValue { max 16 },
+        },
+        characteristic: Model uuid = 0x2B03 {
+            attribute: ModelValue { 4 },
+        },
+    },
+}
+
+#[test]
+fn writes_are_reported_as_events() {
+    use gatt_server::GattEvent;
+
+    let mut server = gatt_server::GattServer::take().unwrap();
+    let mut att = AttServer::new(ConnectionId(7), 23);
+    let mut response = [0; 23];
+
+    let processed = att.process(&mut server, &[0x12, 0x03, 0x00, 0x34, 0x12], &mut response);
+    assert_eq!(
+        processed.response.map(|len| &response[..len]),
+        Some(&[0x13][..])
+    );
+    match processed.event {
+        Some(GattEvent::BrightnessValue { connection, value }) => {
+            assert_eq!(connection, ConnectionId(7));
+            assert_eq!(value, 0x1234);
+        }
+        _ => panic!("Expected a brightness event"),
+    }
+
+    // Commands report their writes without a response.
+    let processed = att.process(&mut server, &[0x52, 0x05, 0x00, b'H', b'i'], &mut response);
+    assert_eq!(processed.response, None);
+    match processed.event {
+        Some(GattEvent::LabelValue {
+            connection,
+            value,
+            len,
+        }) => {
+            assert_eq!(connection, ConnectionId(7));
+            assert_eq!(&value[..len], b"Hi");
+            assert_eq!(value[len..], [0; 14]);
+            assert_eq!(server.lights().label().label_value().get(), b"Hi");
+        }
+        _ => panic!("Expected a label event"),
+    }
+
+    // Rejected writes and reads have no event.
+    let processed = att.process(&mut server, &[0x12, 0x07, 0x00, 1, 2, 3, 4], &mut response);
+    assert_eq!(
+        processed.response.map(|len| &response[..len]),
+        Some(&[0x01, 0x12, 0x07, 0x00, 0x03][..])
+    );
+    assert!(processed.event.is_none());
+    let processed = att.process(&mut server, &[0x0A, 0x03, 0x00], &mut response);
+    assert!(processed.event.is_none());
+
+    // Each write property only allows its own kind of write.
+    let processed = att.process(&mut server, &[0x12, 0x05, 0x00, b'N', b'o'], &mut response);
+    assert_eq!(
+        processed.response.map(|len| &response[..len]),
+        Some(&[0x01, 0x12, 0x05, 0x00, 0x03][..])
+    );
+    assert!(processed.event.is_none());
+    let processed = att.process(&mut server, &[0x52, 0x03, 0x00, 0x00, 0x00], &mut response);
+    assert_eq!(processed.response, None);
+    assert!(processed.event.is_none());
+    assert_eq!(server.lights().label().label_value().get(), b"Hi");
+    assert_eq!(
+        server.lights().brightness().brightness_value().get(),
+        0x1234
+    );
+}
@@ -0,0 +1,51 @@
+use gatt::gatt_server;
+use gatt_runtime::{Attribute, Characteristic, Descriptor, Service};
+
+gatt_runtime::wrapper! {
+    pub struct Battery(Service);
+    pub struct Level(Characteristic);
+    pub struct LevelValue(Attribute);
+    pub struct Description(Descriptor);
+    pub struct DescriptionValue(Attribute);
+    pub struct Info(Service);
+    pub struct Serial(Attribute);
+}
+
+gatt_server! {
+    service: Battery uuid = 0x180F {
+        characteristic: Level uuid = 0x2A19 properties(read, notify) {
+            attribute: LevelValue<u8>,
+            descriptor: Description uuid = 0x2901 {
+                attribute: DescriptionValue = b"Charge",
+            },
+        },
+    },
+    service: Info uuid = "6e400001-b5a3-f393-e0a9-e50e24dcca9e" {
+        attribute: Serial uuid = 0x2A25 permissions(read_encrypted) { max 16 },
+    },
+}
+
+#[test]
+fn database_is_described() {
+    let mut json = String::new();
+    gatt_server::describe(&mut json).unwrap();
+    // Compare without the pretty printing.
+    let json = json.split_whitespace().collect::<String>();
+    assert!(json.starts_with(r#"{"attribute_count":7,"services":[{"name":"battery","#));
+    assert!(json.contains(
+        r#""name":"level","uuid":"00002a19-0000-1000-8000-00805f9b34fb","handle":2,"value_handle":3,"properties":["read","notify"]"#
+    ));
+    // The compiler fills in the size of typed values.
+    assert!(json.contains(r#""name":"level_value","value":{"type":"u8","size":1}"#));
+    // The synthesized client characteristic configuration has no name.
+    assert!(json.contains(
+        r#"{"name":null,"uuid":"00002902-0000-1000-8000-00805f9b34fb","attributes":[{"handle":4,"#
+    ));
+    assert!(json.contains(r#""name":"description_value","value":{"size":6}"#));
+    assert!(json.contains(
+        r#""name":"info","uuid":"6e400001-b5a3-f393-e0a9-e50e24dcca9e","handle":6,"end_handle":7"#
+    ));
+    assert!(json.contains(
+        r#""permissions":["read","read_encrypted"],"name":"serial","value":{"max_size":16}"#
+    ));
+}
@@ -0,0 +1,79 @@
+use gatt::{gatt_client, gatt_server};
+use gatt_runtime::{
+    att::{AttServer, ConnectionId},
+    client::Local,
+    Attribute, Characteristic, Database, Descriptor, Permissions, Service, Uuid,
+};
+
+gatt_runtime::wrapper! {
+    pub struct Battery(Service);
+    pub struct Level(Characteristic);
+    pub struct LevelValue(Attribute);
+    pub struct Description(Descriptor);
+    pub struct DescriptionValue(Attribute);
+    pub struct Configuration(Service);
+    pub struct Serial(Attribute);
+    pub struct Label(Characteristic);
+    pub struct LabelValue(Attribute);
+}
+
+gatt_server!(file = "tests/gatt/sensor.toml");
+gatt_client!(file = "tests/gatt/sensor.toml");
+
+#[test]
+fn tree_is_loaded_from_file() {
+    let mut server = gatt_server::GattServer::take().unwrap();
+
+    assert_eq!(server.battery().level().level_value().get(), 100);
+    assert_eq!(
+        server
+            .battery()
+            .level()
+            .description()
+            .description_value()
+            .get(),
+        *b"Charge"
+    );
+    assert_eq!(server.settings().serial().get(), [4, 3, 2, 1]);
+    assert_eq!(
+        server.settings().label().label_value().get(),
+        b"Living room"
+    );
+
+    // The battery service, its level with a client characteristic configuration and a
+    // description, then the settings with their serial and label.
+    let types = (0..server.attribute_count())
+        .map(|i| server.att_type(i))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        types[..5],
+        [
+            Uuid::Uuid16(0x2800),
+            Uuid::Uuid16(0x2803),
+            Uuid::Uuid16(0x2A19),
+            Uuid::Uuid16(0x2902),
+            Uuid::Uuid16(0x2901),
+        ]
+    );
+    assert_eq!(server.attribute_count(), 9);
+    assert_eq!(
+        server.permissions(8),
+        Permissions::from_bits(
+            Permissions::READ.bits()
+                | Permissions::WRITE.bits()
+                | Permissions::WRITE_ENCRYPTED.bits()
+        )
+    );
+
+    // Both connections have their own subscription.
+    let mut att = AttServer::new(ConnectionId(1), 23);
+    let mut response = [0; 23];
+    att.process(&mut server, &[0x12, 0x04, 0x00, 0x01, 0x00], &mut response);
+    assert_eq!(server.value(ConnectionId(0), 3), &[0, 0]);
+    assert_eq!(server.value(ConnectionId(1), 3), &[1, 0]);
+
+    let mut x = Local::new(&mut att, &mut server);
+    let client = gatt_client::GattClient::discover(&mut x, 23).unwrap();
+    assert_eq!(client.level.value, gatt_server::LevelValueHandle::HANDLE);
+    assert_eq!(client.read_level(&mut x), Ok(100));
+}
@@ -0,0 +1,61 @@
+use gatt::{gatt_server, GattValue};
+use gatt_runtime::{att::ConnectionId, Attribute, Characteristic, Database, Service};
+
+#[derive(Debug, PartialEq, GattValue)]
+pub struct Version {
+    major: u8,
+    minor: u8,
+}
+
+gatt_runtime::wrapper! {
+    pub struct Access(Service);
+    pub struct Name(Characteristic);
+    pub struct NameValue(Attribute);
+    pub struct Appearance(Characteristic);
+    pub struct AppearanceValue(Attribute);
+    pub struct Information(Service);
+    pub struct Revision(Attribute);
+    pub struct Features(Attribute);
+    pub struct Offset(Attribute);
+    pub struct FirmwareVersion(Attribute);
+    pub struct Empty(Attribute);
+}
+
+gatt_server! {
+    service: Access uuid = 0x1800 {
+        characteristic: Name uuid = 0x2A00 {
+            attribute: NameValue = b"Sensor-1",
+        },
+        characteristic: Appearance uuid = 0x2A01 {
+            attribute: AppearanceValue<u16> = 0x0042,
+        },
+    },
+    service: Information uuid = 0x180A {
+        attribute: Revision uuid = 0x2A28 { 4 } = "1.2",
+        attribute: Features uuid = 0x2A29 { 4 } = 0x0102,
+        attribute: Offset uuid = 0x2A2A = -5i16,
+        attribute: FirmwareVersion<Version> uuid = 0x2A26 = 0x0201u16,
+        attribute: Empty uuid = 0x2A27 { 2 },
+    },
+}
+
+#[test]
+fn initializers_are_baked_into_the_store() {
+    let mut server = gatt_server::GattServer::take().unwrap();
+
+    assert_eq!(server.value(ConnectionId(0), 2), b"Sensor-1");
+    assert_eq!(server.value(ConnectionId(0), 4), &[0x42, 0x00]);
+    assert_eq!(server.value(ConnectionId(0), 6), b"1.2\0");
+    assert_eq!(server.value(ConnectionId(0), 7), &[0x02, 0x01, 0x00, 0x00]);
+    assert_eq!(server.value(ConnectionId(0), 8), &[0xFB, 0xFF]);
+    assert_eq!(server.value(ConnectionId(0), 10), &[0, 0]);
+
+    assert_eq!(
+        server.access().appearance().appearance_value().get(),
+        0x0042
+    );
+    assert_eq!(
+        server.information().firmware_version().get(),
+        Version { major: 1, minor: 2 }
+    );
+}
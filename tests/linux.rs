@@ -0,0 +1,81 @@
+#![cfg(target_os = "linux")]
+
+use std::thread;
+
+use gatt::gatt_server;
+use gatt_runtime::{
+    att::ConnectionId,
+    linux::{serve, PduSocket, SeqPacket, SocketTransport},
+    transport::AttTransport,
+    Attribute, Characteristic, Database, Service,
+};
+
+gatt_runtime::wrapper! {
+    pub struct Battery(Service);
+    pub struct Level(Characteristic);
+    pub struct LevelValue(Attribute);
+}
+
+gatt_server! {
+    service: Battery uuid = 0x180F {
+        characteristic: Level uuid = 0x2A19 properties(read, write, notify) {
+            attribute: LevelValue<u8> = 100,
+        },
+    },
+}
+
+#[test]
+fn the_server_answers_over_a_socket() {
+    let (mut client, server_socket) = SeqPacket::pair().unwrap();
+
+    let server = thread::spawn(move || {
+        let mut server = gatt_server::GattServer::take().unwrap();
+        let mut transport = SocketTransport::new(server_socket, ConnectionId(0), 64);
+        let mut levels = vec![];
+        serve(
+            &mut server,
+            &mut transport,
+            |server, att, transport, processed| {
+                if let Some(gatt_server::GattEvent::LevelValue { value, .. }) = processed.event {
+                    levels.push(value);
+                    let mut pdu = [0; 64];
+                    let len = server
+                        .battery()
+                        .level()
+                        .notify(att, value, &mut pdu)
+                        .unwrap();
+                    transport.send(&pdu[..len])?;
+                }
+                Ok(())
+            },
+        )
+        .unwrap();
+        // The subscription of the client is gone with it.
+        assert_eq!(server.value(ConnectionId(0), 3), &[0, 0]);
+        levels
+    });
+
+    let mut response = [0; 64];
+    client.send(&[0x02, 0x40, 0x00]).unwrap();
+    let len = client.receive(&mut response).unwrap();
+    assert_eq!(&response[..len], &[0x03, 0x40, 0x00]);
+
+    client.send(&[0x0A, 0x03, 0x00]).unwrap();
+    let len = client.receive(&mut response).unwrap();
+    assert_eq!(&response[..len], &[0x0B, 100]);
+
+    client.send(&[0x12, 0x04, 0x00, 0x01, 0x00]).unwrap();
+    let len = client.receive(&mut response).unwrap();
+    assert_eq!(&response[..len], &[0x13]);
+
+    // The callback notifies the subscribed client of its own write.
+    client.send(&[0x12, 0x03, 0x00, 42]).unwrap();
+    let len = client.receive(&mut response).unwrap();
+    assert_eq!(&response[..len], &[0x13]);
+    let len = client.receive(&mut response).unwrap();
+    assert_eq!(&response[..len], &[0x1B, 0x03, 0x00, 42]);
+
+    // Disconnecting ends the server loop.
+    drop(client);
+    assert_eq!(server.join().unwrap(), vec![42]);
+}
@@ -0,0 +1,105 @@
+use gatt::gatt_server;
+use gatt_runtime::{
+    att::{AttServer, ConnectionId, SecurityLevel},
+    transport::{AttTransport, Loopback},
+    Attribute, Characteristic, Service,
+};
+
+gatt_runtime::wrapper! {
+    pub struct Battery(Service);
+    pub struct Level(Characteristic);
+    pub struct LevelValue(Attribute);
+    pub struct Secret(Characteristic);
+    pub struct SecretValue(Attribute);
+}
+
+gatt_server! {
+    service: Battery uuid = 0x180F {
+        characteristic: Level uuid = 0x2A19 properties(read, indicate) {
+            attribute: LevelValue<u8> = 100,
+        },
+        characteristic: Secret uuid = 0xFF01 permissions(read_encrypted) {
+            attribute: SecretValue<u16> = 0x1234,
+        },
+    },
+}
+
+#[test]
+fn the_server_answers_over_a_loopback() {
+    let mut server = gatt_server::GattServer::take().unwrap();
+    let mut link = Loopback::<128>::new(ConnectionId(0), 23);
+    let mut att = AttServer::for_transport(&link);
+    let mut request = [0; 23];
+    let mut response = [0; 23];
+    let mut received = [0; 23];
+
+    // Nothing to do until the client sends something.
+    assert!(att
+        .poll(&mut server, &mut link, &mut request, &mut response)
+        .unwrap()
+        .is_none());
+
+    // Discover the services and read the level.
+    link.send_to_server(&[0x10, 0x01, 0x00, 0xFF, 0xFF, 0x00, 0x28])
+        .unwrap();
+    link.send_to_server(&[0x0A, 0x03, 0x00]).unwrap();
+    while att
+        .poll(&mut server, &mut link, &mut request, &mut response)
+        .unwrap()
+        .is_some()
+    {}
+    let len = link.receive_from_server(&mut received).unwrap();
+    assert_eq!(
+        &received[..len],
+        &[0x11, 0x06, 0x01, 0x00, 0x06, 0x00, 0x0F, 0x18]
+    );
+    let len = link.receive_from_server(&mut received).unwrap();
+    assert_eq!(&received[..len], &[0x0B, 100]);
+    assert_eq!(link.receive_from_server(&mut received), None);
+
+    // The security of the transport decides over encrypted reads.
+    link.send_to_server(&[0x0A, 0x06, 0x00]).unwrap();
+    att.poll(&mut server, &mut link, &mut request, &mut response)
+        .unwrap();
+    let len = link.receive_from_server(&mut received).unwrap();
+    assert_eq!(&received[..len], &[0x01, 0x0A, 0x06, 0x00, 0x0F]);
+    link.set_security(SecurityLevel::Encrypted);
+    link.send_to_server(&[0x0A, 0x06, 0x00]).unwrap();
+    att.poll(&mut server, &mut link, &mut request, &mut response)
+        .unwrap();
+    let len = link.receive_from_server(&mut received).unwrap();
+    assert_eq!(&received[..len], &[0x0B, 0x34, 0x12]);
+
+    // Indications travel over the transport and come back confirmed.
+    link.send_to_server(&[0x12, 0x04, 0x00, 0x02, 0x00])
+        .unwrap();
+    att.poll(&mut server, &mut link, &mut request, &mut response)
+        .unwrap();
+    assert_eq!(link.receive_from_server(&mut received), Some(1));
+    let len = server
+        .battery()
+        .level()
+        .indicate(&mut att, 99, &mut response)
+        .unwrap();
+    link.send(&response[..len]).unwrap();
+    let len = link.receive_from_server(&mut received).unwrap();
+    assert_eq!(&received[..len], &[0x1D, 0x03, 0x00, 99]);
+    link.send_to_server(&[0x1E]).unwrap();
+    let processed = att
+        .poll(&mut server, &mut link, &mut request, &mut response)
+        .unwrap()
+        .unwrap();
+    assert_eq!(processed.confirmed, Some(0x0003));
+    assert_eq!(link.receive_from_server(&mut received), None);
+}
+
+#[test]
+fn full_loopbacks_refuse_pdus() {
+    let mut link = Loopback::<8>::new(ConnectionId(0), 23);
+    link.send_to_server(&[1, 2, 3]).unwrap();
+    assert!(link.send_to_server(&[1, 2, 3, 4]).is_err());
+    let mut buffer = [0; 8];
+    assert_eq!(link.receive(&mut buffer), Ok(Some(3)));
+    assert_eq!(&buffer[..3], &[1, 2, 3]);
+    assert_eq!(link.receive(&mut buffer), Ok(None));
+}
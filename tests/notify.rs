@@ -0,0 +1,98 @@
+use gatt::gatt_server;
+use gatt_runtime::{
+    att::{AttServer, ConnectionId, NotifyError},
+    Attribute, Characteristic, Service,
+};
+
+gatt_runtime::wrapper! {
+    pub struct Battery(Service);
+    pub struct Level(Characteristic);
+    pub struct LevelValue(Attribute);
+    pub struct Status(Characteristic);
+    pub struct StatusValue(Attribute);
+}
+
+gatt_server! {
+    service: Battery uuid = 0x180F {
+        characteristic: Level uuid = 0x2A19 properties(read, notify, indicate) {
+            attribute: LevelValue<u8>,
+        },
+        characteristic: Status uuid = 0x2BED properties(notify) {
+            attribute: StatusValue { max 30 },
+        },
+    },
+}
+
+#[test]
+fn notifications_and_indications() {
+    let mut server = gatt_server::GattServer::take().unwrap();
+    let mut att = AttServer::new(ConnectionId(0), 23);
+    let mut response = [0; 23];
+    let mut pdu = [0; 23];
+
+    let mut battery = server.battery();
+    assert_eq!(
+        battery.level().notify(&att, 90, &mut pdu),
+        Err(NotifyError::NotSubscribed)
+    );
+    // The value is stored regardless.
+    assert_eq!(battery.level().level_value().get(), 90);
+
+    // Subscribe to notifications of the level.
+    let processed = att.process(&mut server, &[0x12, 0x04, 0x00, 0x01, 0x00], &mut response);
+    assert_eq!(
+        processed.response.map(|len| &response[..len]),
+        Some(&[0x13][..])
+    );
+
+    let len = server.battery().level().notify(&att, 80, &mut pdu).unwrap();
+    assert_eq!(&pdu[..len], &[0x1B, 0x03, 0x00, 80]);
+    assert_eq!(
+        server.battery().level().indicate(&mut att, 70, &mut pdu),
+        Err(NotifyError::NotSubscribed)
+    );
+
+    // Long values are truncated to the MTU.
+    att.process(&mut server, &[0x12, 0x07, 0x00, 0x01, 0x00], &mut response);
+    let len = server
+        .battery()
+        .status()
+        .notify(&att, &[7; 30], &mut pdu)
+        .unwrap();
+    assert_eq!(len, 23);
+    assert_eq!(&pdu[..3], &[0x1B, 0x06, 0x00]);
+    assert_eq!(
+        server.battery().status().notify(&att, &[7; 31], &mut pdu),
+        Err(NotifyError::ValueTooLong)
+    );
+
+    // Switch the subscription of the level to indications.
+    att.process(&mut server, &[0x12, 0x04, 0x00, 0x02, 0x00], &mut response);
+
+    let len = server
+        .battery()
+        .level()
+        .indicate(&mut att, 60, &mut pdu)
+        .unwrap();
+    assert_eq!(&pdu[..len], &[0x1D, 0x03, 0x00, 60]);
+    assert_eq!(
+        server.battery().level().indicate(&mut att, 50, &mut pdu),
+        Err(NotifyError::IndicationPending)
+    );
+
+    let processed = att.process(&mut server, &[0x1E], &mut response);
+    assert_eq!(processed.response, None);
+    assert_eq!(processed.confirmed, Some(0x0003));
+    // Only outstanding indications are confirmed.
+    assert_eq!(
+        att.process(&mut server, &[0x1E], &mut response).confirmed,
+        None
+    );
+
+    let len = server
+        .battery()
+        .level()
+        .indicate(&mut att, 50, &mut pdu)
+        .unwrap();
+    assert_eq!(&pdu[..len], &[0x1D, 0x03, 0x00, 50]);
+}
@@ -0,0 +1,90 @@
+use gatt::{gatt_client, gatt_server};
+use gatt_runtime::{
+    att::{ConnectionId, ErrorCode},
+    client::{AttClient, ClientError, Remote},
+    transport::{AttTransport, Loopback},
+    Attribute, Characteristic, Service,
+};
+
+gatt_runtime::wrapper! {
+    pub struct Battery(Service);
+    pub struct Level(Characteristic);
+    pub struct LevelValue(Attribute);
+}
+
+/// The server and the client are generated from the same tree.
+macro_rules! battery {
+    ($generate:ident) => {
+        $generate! {
+            service: Battery uuid = 0x180F {
+                characteristic: Level uuid = 0x2A19 properties(read, write) {
+                    attribute: LevelValue<u8> = 100,
+                },
+            },
+        }
+    };
+}
+
+battery!(gatt_server);
+battery!(gatt_client);
+
+#[test]
+fn requests_go_through_the_transport() {
+    let mut loopback = Loopback::<64>::new(ConnectionId(0), 23);
+    // The answers of the server are queued ahead and handed out one per request.
+    loopback.send(&[0x0B, 100]).unwrap();
+    loopback.send(&[0x01, 0x12, 0x03, 0x00, 0x03]).unwrap();
+
+    let client = AttClient::new();
+    let mut x = Remote::new(loopback.client());
+    let mut value = [0; 1];
+    assert_eq!(client.read(&mut x, 3, &mut value), Ok(1));
+    assert_eq!(value, [100]);
+    assert!(client
+        .write(&mut x, 3, &[42])
+        .unwrap_err()
+        .is(ErrorCode::WriteNotPermitted));
+    assert_eq!(
+        client.read(&mut x, 3, &mut value),
+        Err(ClientError::UnexpectedResponse)
+    );
+    client.confirm(&mut x).unwrap();
+
+    let mut pdu = [0; 23];
+    let requests: [&[u8]; 4] = [
+        &[0x0A, 0x03, 0x00],
+        &[0x12, 0x03, 0x00, 42],
+        &[0x0A, 0x03, 0x00],
+        &[0x1E],
+    ];
+    for request in requests.iter() {
+        let len = loopback.receive(&mut pdu).unwrap().unwrap();
+        assert_eq!(&pdu[..len], *request);
+    }
+    assert_eq!(loopback.receive(&mut pdu), Ok(None));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn the_client_talks_to_a_served_socket() {
+    use std::thread;
+
+    use gatt_runtime::linux::{serve, SeqPacket, SocketTransport};
+
+    let (client_socket, server_socket) = SeqPacket::pair().unwrap();
+    let server = thread::spawn(move || {
+        let mut server = gatt_server::GattServer::take().unwrap();
+        let mut transport = SocketTransport::new(server_socket, ConnectionId(0), 64);
+        serve(&mut server, &mut transport, |_, _, _, _| Ok(())).unwrap();
+    });
+
+    let mut x = Remote::new(SocketTransport::new(client_socket, ConnectionId(0), 64));
+    let client = gatt_client::GattClient::discover(&mut x, 64).unwrap();
+    assert_eq!(client.read_level(&mut x).unwrap(), 100);
+    client.write_level(&mut x, 42).unwrap();
+    assert_eq!(client.read_level(&mut x).unwrap(), 42);
+
+    // Disconnecting ends the server loop.
+    drop(x);
+    server.join().unwrap();
+}
@@ -0,0 +1,31 @@
+use gatt::gatt_server;
+use gatt_runtime::{att::ConnectionId, Attribute, Characteristic, Database, Service};
+
+gatt_runtime::wrapper! {
+    pub struct Device(Service);
+    pub struct Model(Characteristic);
+    pub struct Serial(Characteristic);
+    pub struct Text(Attribute);
+}
+
+// Read-only attributes may share a type, its handle type reaches each of them.
+gatt_server! {
+    service: Device uuid = 0x180A {
+        characteristic: Model uuid = 0x2A24 {
+            attribute: Text { 4 } = b"GT-1",
+        },
+        characteristic: Serial uuid = 0x2A25 {
+            attribute: Text { 4 } = b"0042",
+        },
+    },
+}
+
+#[test]
+fn attributes_share_their_type() {
+    let mut server = gatt_server::GattServer::take().unwrap();
+
+    assert_eq!(server.device().model().text().get(), *b"GT-1");
+    server.device().serial().text().set(*b"0043");
+    assert_eq!(server.value(ConnectionId(0), 2), b"GT-1");
+    assert_eq!(server.value(ConnectionId(0), 4), b"0043");
+}
@@ -0,0 +1,67 @@
+//! Exercises reads and writes of the attribute store. Run under Miri with `cargo +nightly miri test`.
+
+use gatt::gatt_server;
+use gatt_runtime::{
+    att::{AttServer, ConnectionId, ErrorCode},
+    Attribute, Characteristic, Database, Descriptor, Service, Uuid,
+};
+
+gatt_runtime::wrapper! {
+    pub struct Battery(Service);
+    pub struct Level(Characteristic);
+    pub struct LevelValue(Attribute);
+    pub struct Format(Descriptor);
+    pub struct FormatValue(Attribute);
+}
+
+gatt_server! {
+    service: Battery uuid = 0x180F {
+        characteristic: Level uuid = 0x2A19 properties(read, write) {
+            attribute: LevelValue { 1 },
+            descriptor: Format uuid = 0x2904 permissions(read, write) {
+                attribute: FormatValue { 7 },
+            },
+        },
+    },
+}
+
+#[test]
+fn reads_see_previous_writes() {
+    let mut server = gatt_server::GattServer::take().unwrap();
+
+    server.write(ConnectionId(0), 2, &[42]).unwrap();
+    assert_eq!(server.value(ConnectionId(0), 2), &[42]);
+
+    server
+        .write(ConnectionId(0), 3, &[1, 2, 3, 4, 5, 6, 7])
+        .unwrap();
+    assert_eq!(server.value(ConnectionId(0), 3), &[1, 2, 3, 4, 5, 6, 7]);
+    assert_eq!(server.value(ConnectionId(0), 2), &[42]);
+
+    let mut att = AttServer::new(ConnectionId(0), 23);
+    let mut response = [0; 23];
+    let len = att
+        .process(&mut server, &[0x12, 0x03, 0x00, 7], &mut response)
+        .response;
+    assert_eq!(len.map(|len| &response[..len]), Some(&[0x13][..]));
+    let len = att
+        .process(&mut server, &[0x0A, 0x03, 0x00], &mut response)
+        .response;
+    assert_eq!(len.map(|len| &response[..len]), Some(&[0x0B, 7][..]));
+
+    let battery = server.battery();
+    assert_eq!(battery.0.uuid, Uuid::Uuid16(0x180F));
+
+    // Invalid writes leave the values untouched.
+    assert_eq!(
+        server.write(ConnectionId(0), 0, &[0, 0]).err(),
+        Some(ErrorCode::WriteNotPermitted)
+    );
+    assert_eq!(server.value(ConnectionId(0), 0), &[0x0F, 0x18]);
+
+    assert_eq!(
+        server.write(ConnectionId(0), 3, &[1, 2]).err(),
+        Some(ErrorCode::InvalidAttributeValueLength)
+    );
+    assert_eq!(server.value(ConnectionId(0), 3).len(), 7);
+}
@@ -0,0 +1,25 @@
+use gatt::gatt_server;
+use gatt_runtime::{Attribute, Characteristic, Service};
+
+gatt_runtime::wrapper! {
+    pub struct Battery(Service);
+    pub struct Level(Characteristic);
+    pub struct LevelValue(Attribute);
+}
+
+gatt_server! {
+    service: Battery uuid = 0x180F {
+        characteristic: Level uuid = 0x2A19 {
+            attribute: LevelValue { 1 },
+        },
+    },
+}
+
+#[test]
+fn take_is_one_shot() {
+    assert!(gatt_server::GattServer::take().is_some());
+    assert!(gatt_server::GattServer::take().is_none());
+    assert!(gatt_server::GattServer::take().is_none());
+
+    let _stolen = unsafe { gatt_server::GattServer::steal() };
+}
@@ -0,0 +1,44 @@
+use gatt::gatt_server;
+use gatt_runtime::{Attribute, Characteristic, Service};
+
+gatt_runtime::wrapper! {
+    pub struct ServiceA(Service);
+    pub struct ServiceB(Service);
+    pub struct ServiceC(Service);
+    pub struct ServiceD(Service);
+    pub struct ServiceE(Service);
+    pub struct BatteryLevel(Characteristic);
+    pub struct CurrentTime(Characteristic);
+    pub struct Temperature(Characteristic);
+    pub struct TemperatureValue(Attribute);
+    pub struct PnpId(Characteristic);
+    pub struct PnpIdValue(Attribute);
+    pub struct Report(Characteristic);
+    pub struct ReportValue(Attribute);
+}
+
+gatt_server! {
+    service: ServiceA uuid = 0xFFF0 {
+        characteristic: BatteryLevel properties(read, write),
+    },
+    service: ServiceB uuid = 0xFFF1 {
+        characteristic: CurrentTime properties(read),
+    },
+    service: ServiceC uuid = 0xFFF2 {
+        characteristic: Temperature {
+            attribute: TemperatureValue<u16>,
+        },
+    },
+    service: ServiceD uuid = 0xFFF3 {
+        characteristic: PnpId {
+            attribute: PnpIdValue { 6 },
+        },
+    },
+    service: ServiceE uuid = 0xFFF4 {
+        characteristic: Report {
+            attribute: ReportValue { max 8 },
+        },
+    },
+}
+
+fn main() {}
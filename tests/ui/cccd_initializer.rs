@@ -0,0 +1,23 @@
+use gatt::gatt_server;
+use gatt_runtime::{Attribute, Characteristic, Descriptor, Service};
+
+gatt_runtime::wrapper! {
+    pub struct ServiceA(Service);
+    pub struct CharacteristicA(Characteristic);
+    pub struct AttributeA(Attribute);
+    pub struct Configuration(Descriptor);
+    pub struct ConfigurationValue(Attribute);
+}
+
+gatt_server! {
+    service: ServiceA uuid = 0x180A {
+        characteristic: CharacteristicA uuid = 0x2A00 properties(notify) {
+            attribute: AttributeA { 1 },
+            descriptor: Configuration uuid = 0x2902 {
+                attribute: ConfigurationValue<u16> = 1,
+            },
+        },
+    },
+}
+
+fn main() {}
@@ -0,0 +1,23 @@
+use gatt::gatt_server;
+use gatt_runtime::{Attribute, Characteristic, Descriptor, Service};
+
+gatt_runtime::wrapper! {
+    pub struct ServiceA(Service);
+    pub struct CharacteristicA(Characteristic);
+    pub struct Configuration(Descriptor);
+    pub struct AttributeA(Attribute);
+    pub struct AttributeB(Attribute);
+}
+
+gatt_server! {
+    service: ServiceA uuid = 0x180F {
+        characteristic: CharacteristicA uuid = 0x2A19 properties(read) {
+            attribute: AttributeA { 1 },
+            descriptor: Configuration uuid = 0x2902 {
+                attribute: AttributeB { 2 },
+            },
+        },
+    },
+}
+
+fn main() {}
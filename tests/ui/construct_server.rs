@@ -0,0 +1,21 @@
+use gatt::gatt_server;
+use gatt_runtime::{Attribute, Characteristic, Service};
+
+gatt_runtime::wrapper! {
+    pub struct Battery(Service);
+    pub struct Level(Characteristic);
+    pub struct LevelValue(Attribute);
+}
+
+gatt_server! {
+    service: Battery uuid = 0x180F {
+        characteristic: Level uuid = 0x2A19 {
+            attribute: LevelValue { 1 },
+        },
+    },
+}
+
+fn main() {
+    // Only `take` and `steal` hand out the server.
+    let _server = gatt_server::GattServer {};
+}
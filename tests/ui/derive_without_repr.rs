@@ -0,0 +1,15 @@
+use gatt::GattValue;
+
+#[derive(GattValue)]
+pub enum Unit {
+    Celsius,
+    Fahrenheit,
+}
+
+#[derive(GattValue)]
+#[repr(u8)]
+pub enum Reading {
+    Celsius(i16),
+}
+
+fn main() {}
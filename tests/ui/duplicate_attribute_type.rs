@@ -0,0 +1,22 @@
+use gatt::gatt_server;
+use gatt_runtime::{Attribute, Characteristic, Service};
+
+gatt_runtime::wrapper! {
+    pub struct ServiceA(Service);
+    pub struct CharacteristicA(Characteristic);
+    pub struct CharacteristicB(Characteristic);
+    pub struct AttributeA(Attribute);
+}
+
+gatt_server! {
+    service: ServiceA uuid = 0x180F {
+        characteristic: CharacteristicA uuid = 0x2A19 {
+            attribute: AttributeA { 1 },
+        },
+        characteristic: CharacteristicB uuid = 0x2A1A properties(read, write) {
+            attribute a: AttributeA { 1 },
+        },
+    },
+}
+
+fn main() {}
@@ -0,0 +1,20 @@
+use gatt::gatt_server;
+use gatt_runtime::{Attribute, Characteristic, Descriptor, Service};
+
+gatt_runtime::wrapper! {
+    pub struct ServiceA(Service);
+    pub struct CharacteristicA(Characteristic);
+    pub struct AttributeA(Attribute);
+    pub struct DescriptorA(Descriptor);
+}
+
+gatt_server! {
+    service: ServiceA uuid = 0x180F {
+        characteristic: CharacteristicA uuid = 0x2A19 {
+            attribute: AttributeA<u8>,
+            descriptor: DescriptorA uuid = 0x2901 {},
+        },
+    },
+}
+
+fn main() {}
@@ -0,0 +1,25 @@
+use gatt::gatt_server;
+use gatt_runtime::{Attribute, Service};
+
+gatt_runtime::wrapper! {
+    pub struct ServiceA(Service);
+    pub struct ServiceB(Service);
+    pub struct ServiceC(Service);
+    pub struct Revision(Attribute);
+    pub struct Model(Attribute);
+    pub struct Hardware(Attribute);
+}
+
+gatt_server! {
+    service: ServiceA uuid = 0x180A {
+        attribute: Revision uuid = 0x2A28 { 2 } = b"1.2.3",
+    },
+    service: ServiceB uuid = 0x180B {
+        attribute: Model uuid = 0x2A24 { 1 } = 0x0100,
+    },
+    service: ServiceC uuid = 0x180C {
+        attribute: Hardware<u8> uuid = 0x2A27 = 0x1234,
+    },
+}
+
+fn main() {}
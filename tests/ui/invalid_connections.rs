@@ -0,0 +1,16 @@
+use gatt::gatt_server;
+use gatt_runtime::{Attribute, Service};
+
+gatt_runtime::wrapper! {
+    pub struct ServiceA(Service);
+    pub struct AttributeA(Attribute);
+}
+
+gatt_server! {
+    connections = 0,
+    service: ServiceA uuid = 0x180A {
+        attribute: AttributeA uuid = 0x2A00 { 1 },
+    },
+}
+
+fn main() {}
@@ -0,0 +1,27 @@
+use gatt::gatt_server;
+use gatt_runtime::{Attribute, Characteristic, Descriptor, Service};
+
+gatt_runtime::wrapper! {
+    pub struct ServiceA(Service);
+    pub struct ServiceB(Service);
+    pub struct CharacteristicA(Characteristic);
+    pub struct DescriptorA(Descriptor);
+    pub struct AttributeA(Attribute);
+    pub struct AttributeB(Attribute);
+}
+
+gatt_server! {
+    characteristic: CharacteristicA uuid = 0x2A19 {
+        attribute: AttributeA { 1 },
+    },
+    service: ServiceA uuid = 0x180F {
+        descriptor: DescriptorA uuid = 0x2901 {
+            service: ServiceB uuid = 0x180A,
+        },
+        attribute: AttributeB uuid = 0x2A00 {
+            attribute: AttributeA { 1 },
+        },
+    },
+}
+
+fn main() {}
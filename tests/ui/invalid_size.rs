@@ -0,0 +1,20 @@
+use gatt::gatt_server;
+use gatt_runtime::{Attribute, Characteristic, Service};
+
+gatt_runtime::wrapper! {
+    pub struct ServiceA(Service);
+    pub struct CharacteristicA(Characteristic);
+    pub struct AttributeA(Attribute);
+    pub struct AttributeB(Attribute);
+}
+
+gatt_server! {
+    service: ServiceA uuid = 0x180F {
+        characteristic: CharacteristicA uuid = 0x2A19 {
+            attribute: AttributeA { 100000000000000000000000 },
+        },
+        attribute: AttributeB uuid = 0x2A00 { 100000000000000000000000 },
+    },
+}
+
+fn main() {}
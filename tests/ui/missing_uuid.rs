@@ -0,0 +1,23 @@
+use gatt::gatt_server;
+use gatt_runtime::{Attribute, Characteristic, Service};
+
+gatt_runtime::wrapper! {
+    pub struct ServiceA(Service);
+    pub struct ServiceB(Service);
+    pub struct CharacteristicA(Characteristic);
+    pub struct AttributeA(Attribute);
+    pub struct AttributeB(Attribute);
+}
+
+gatt_server! {
+    service: ServiceA uuid = 0x180F {
+        characteristic: CharacteristicA {
+            attribute: AttributeA { 1 },
+        },
+    },
+    service: ServiceB uuid = 0x180A {
+        attribute: AttributeB { 1 },
+    },
+}
+
+fn main() {}
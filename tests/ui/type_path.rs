@@ -0,0 +1,21 @@
+use gatt::gatt_server;
+
+mod types {
+    use gatt_runtime::{Attribute, Characteristic, Service};
+
+    gatt_runtime::wrapper! {
+        pub struct ServiceA(Service);
+        pub struct CharacteristicA(Characteristic);
+        pub struct AttributeA(Attribute);
+    }
+}
+
+gatt_server! {
+    service: types::ServiceA uuid = 0x180F {
+        characteristic: CharacteristicA uuid = 0x2A19 {
+            attribute a: ::types::AttributeA<u8>,
+        },
+    },
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use gatt::gatt_server;
+use gatt_runtime::{Attribute, Service};
+
+gatt_runtime::wrapper! {
+    pub struct Information(Service);
+    pub struct Serial(Attribute);
+}
+
+gatt_server! {
+    service: Information uuid = 0x180A {
+        attribute: Serial<u8> uuid = 0x2A25 = 0x0100u16,
+    },
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+use gatt::gatt_server;
+use gatt_runtime::{Attribute, Characteristic, Service};
+
+gatt_runtime::wrapper! {
+    pub struct BloodPressure(Service);
+    pub struct BloodPressureMeasurement(Characteristic);
+    pub struct MeasurementValue(Attribute);
+}
+
+// Blood pressure is assigned by the SIG but not among the numbers the macro bundles.
+gatt_server! {
+    service: BloodPressure uuid = 0x1810 {
+        characteristic: BloodPressureMeasurement properties(indicate) {
+            attribute: MeasurementValue { max 19 },
+        },
+    },
+}
+
+fn main() {}
@@ -0,0 +1,20 @@
+use gatt::gatt_server;
+use gatt_runtime::{Attribute, Service};
+
+gatt_runtime::wrapper! {
+    pub struct ServiceA(Service);
+    pub struct ServiceB(Service);
+    pub struct Name(Attribute);
+    pub struct Label(Attribute);
+}
+
+gatt_server! {
+    service: ServiceA uuid = 0x180A {
+        attribute: Name uuid = 0x2A00 { max 600 },
+    },
+    service: ServiceB uuid = 0x180B {
+        attribute: Label uuid = 0x2A01 { max 4 } = "Kitchen",
+    },
+}
+
+fn main() {}
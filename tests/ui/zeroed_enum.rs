@@ -0,0 +1,25 @@
+use gatt::{gatt_server, GattValue};
+use gatt_runtime::{Attribute, Characteristic, Service};
+
+#[derive(Clone, Copy, GattValue)]
+#[repr(u8)]
+pub enum Unit {
+    Celsius = 1,
+    Kelvin,
+}
+
+gatt_runtime::wrapper! {
+    pub struct ServiceA(Service);
+    pub struct CharacteristicA(Characteristic);
+    pub struct AttributeA(Attribute);
+}
+
+gatt_server! {
+    service: ServiceA uuid = 0x181A {
+        characteristic: CharacteristicA uuid = 0x2A6F properties(read, write) {
+            attribute: AttributeA<Unit>,
+        },
+    },
+}
+
+fn main() {}
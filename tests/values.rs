@@ -0,0 +1,69 @@
+use gatt::gatt_server;
+use gatt_runtime::{
+    att::{AttServer, ConnectionId},
+    Attribute, Characteristic, Database, Descriptor, GattValue, Service,
+};
+
+gatt_runtime::wrapper! {
+    pub struct Environment(Service);
+    pub struct Temperature(Characteristic);
+    pub struct TemperatureValue(Attribute);
+    pub struct Valid(Descriptor);
+    pub struct ValidValue(Attribute);
+    pub struct Range(Characteristic);
+    pub struct RangeValue(Attribute);
+    pub struct Raw(Characteristic);
+    pub struct RawValue(Attribute);
+}
+
+gatt_server! {
+    service: Environment uuid = 0x181A {
+        characteristic: Temperature uuid = 0x2A6E properties(read, write) {
+            attribute: TemperatureValue<i16>,
+            descriptor: Valid uuid = 0x2901 {
+                attribute: ValidValue<bool>,
+            },
+        },
+        characteristic: Range uuid = 0x2AE1 properties(read, write) {
+            attribute: RangeValue<[u16; 2]>,
+        },
+        characteristic: Raw uuid = 0x2A00 {
+            attribute: RawValue { 3 },
+        },
+    },
+}
+
+#[test]
+fn typed_values_are_little_endian() {
+    assert_eq!(<i16 as GattValue>::SIZE, 2);
+    assert_eq!(<[u16; 2] as GattValue>::SIZE, 4);
+
+    let mut server = gatt_server::GattServer::take().unwrap();
+    let mut environment = server.environment();
+
+    environment.temperature().temperature_value().set(-2);
+    environment.temperature().valid().valid_value().set(true);
+    environment.range().range_value().set([0x0102, 0x0304]);
+    environment.raw().raw_value().set([1, 2, 3]);
+
+    assert_eq!(environment.temperature().temperature_value().get(), -2);
+    assert!(environment.temperature().valid().valid_value().get());
+    assert_eq!(environment.range().range_value().get(), [0x0102, 0x0304]);
+    assert_eq!(environment.raw().raw_value().get(), [1, 2, 3]);
+
+    assert_eq!(server.value(ConnectionId(0), 2), &[0xFE, 0xFF]);
+    assert_eq!(server.value(ConnectionId(0), 3), &[0x01]);
+    assert_eq!(server.value(ConnectionId(0), 5), &[0x02, 0x01, 0x04, 0x03]);
+    assert_eq!(server.value(ConnectionId(0), 7), &[1, 2, 3]);
+
+    let mut att = AttServer::new(ConnectionId(0), 23);
+    let mut response = [0; 23];
+    let len = att
+        .process(&mut server, &[0x12, 0x03, 0x00, 0x19, 0x00], &mut response)
+        .response;
+    assert_eq!(len.map(|len| &response[..len]), Some(&[0x13][..]));
+    assert_eq!(
+        server.environment().temperature().temperature_value().get(),
+        25
+    );
+}
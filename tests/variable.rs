@@ -0,0 +1,90 @@
+use gatt::gatt_server;
+use gatt_runtime::{
+    att::{AttServer, ConnectionId, ErrorCode},
+    Attribute, Characteristic, Database, Service,
+};
+
+gatt_runtime::wrapper! {
+    pub struct Access(Service);
+    pub struct Name(Characteristic);
+    pub struct NameValue(Attribute);
+    pub struct Label(Attribute);
+}
+
+gatt_server! {
+    service: Access uuid = 0x1800 {
+        characteristic: Name uuid = 0x2A00 properties(read, write) {
+            attribute: NameValue { max 32 } = "Sensor",
+        },
+        attribute: Label uuid = 0x2A01 permissions(read, write) { max 4 },
+    },
+}
+
+#[test]
+fn variable_length_values() {
+    let mut server = gatt_server::GattServer::take().unwrap();
+
+    let mut access = server.access();
+    let mut name = access.name();
+    assert_eq!(name.name_value().get(), b"Sensor");
+    assert_eq!(name.name_value().len(), 6);
+    assert_eq!(gatt_server::NameValueHandle::MAX_LEN, 32);
+
+    name.name_value().set(b"Kitchen").unwrap();
+    assert_eq!(name.name_value().get(), b"Kitchen");
+    assert_eq!(
+        name.name_value().set(&[0; 33]),
+        Err(ErrorCode::InvalidAttributeValueLength)
+    );
+    assert_eq!(name.name_value().get(), b"Kitchen");
+
+    assert!(access.label().is_empty());
+    access.label().set(b"abcd").unwrap();
+    assert_eq!(access.label().get(), b"abcd");
+
+    let mut att = AttServer::new(ConnectionId(0), 23);
+    let mut response = [0; 23];
+
+    // Read Request
+    let len = att
+        .process(&mut server, &[0x0A, 0x04, 0x00], &mut response)
+        .response
+        .unwrap();
+    assert_eq!(&response[..len], b"\x0BKitchen");
+
+    // Write Request with a shorter value
+    let len = att
+        .process(
+            &mut server,
+            &[0x12, 0x04, 0x00, b'H', b'a', b'l', b'l'],
+            &mut response,
+        )
+        .response
+        .unwrap();
+    assert_eq!(&response[..len], &[0x13]);
+    assert_eq!(server.value(ConnectionId(0), 3), b"Hall");
+
+    // Read Blob Request past the valid bytes
+    let len = att
+        .process(&mut server, &[0x0C, 0x04, 0x00, 0x02, 0x00], &mut response)
+        .response
+        .unwrap();
+    assert_eq!(&response[..len], b"\x0Dll");
+    let len = att
+        .process(&mut server, &[0x0C, 0x04, 0x00, 0x05, 0x00], &mut response)
+        .response
+        .unwrap();
+    assert_eq!(&response[..len], &[0x01, 0x0C, 0x04, 0x00, 0x07]);
+
+    // Write Request longer than the maximum
+    let len = att
+        .process(
+            &mut server,
+            &[0x12, 0x02, 0x00, 1, 2, 3, 4, 5],
+            &mut response,
+        )
+        .response
+        .unwrap();
+    assert_eq!(&response[..len], &[0x01, 0x12, 0x02, 0x00, 0x0D]);
+    assert_eq!(server.value(ConnectionId(0), 1), b"abcd");
+}